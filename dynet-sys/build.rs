@@ -12,6 +12,11 @@ const DYNET_REPOSITORY:&'static str = "https://github.com/clab/dynet.git";
 const DYNET_LIBRARY:&'static str = "dynet";
 const DYNETC_REPOSITORY:&'static str = "https://github.com/xbainbain/dynet-c.git";
 const DYNETC_LIBRARY:&'static str = "dynetc";
+// The bindings need a dynet-c revision providing the functions used by the
+// crate, e.g. the state accessors of `DN_RNNBuilder` returning the number of
+// states written, `DN_CustomOp` and `DN_NewFloatVector`, so the revision is
+// pinned instead of following master.
+const DYNETC_REVISION_VAR:&'static str = "DYNETC_REVISION";
 
 fn main() {
     check_prerequisites();
@@ -105,6 +110,7 @@ fn build_from_src() {
             }
         }
     }
+    checkout_dynetc(&dynetc_dir);
 
     // Build dynet
     if dynet_lib_path.exists() {
@@ -177,6 +183,20 @@ fn build_from_src() {
     println!("cargo:rustc-link-search={}", dynetc_lib_dir.display());
 }
 
+fn checkout_dynetc(dynetc_dir: &PathBuf) {
+    println!("cargo:rerun-if-env-changed={}", DYNETC_REVISION_VAR);
+    let revision = env::var(DYNETC_REVISION_VAR).unwrap_or_else(|_| {
+        panic!("Set {} to the dynet-c revision to build against", DYNETC_REVISION_VAR)
+    });
+    let status = Command::new("git")
+                         .current_dir(dynetc_dir)
+                         .args(&["checkout", "--quiet", &revision])
+                         .status()
+                         .expect("Unable to run git");
+    if !status.success() {
+        panic!("Unable to check out revision {} of dynet-c at {:?}", revision, dynetc_dir);
+    }
+}
 
 fn create_bindings() {
     let bindings = bindgen::Builder::default()
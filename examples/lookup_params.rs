@@ -1,6 +1,8 @@
 #![allow(non_snake_case)]
 extern crate dynet as dy;
 
+use dy::ops::*;
+
 fn main() {
     dy::initialize();
     let m = dy::ParameterCollection::new();
@@ -9,4 +11,17 @@ fn main() {
     let dim = 10;
     let lp = m.add_default_lookup_param(&[vocab_size, dim]);
 
-}
\ No newline at end of file
+    let cg = dy::ComputationGraph::new();
+
+    // Look up a single embedding.
+    let e = cg.lookup(&lp, 5);
+    e.print();
+
+    // Look up a batch of embeddings and sum them.
+    let es = cg.lookup_batch(&lp, &[1, 2, 3]);
+    sum_batches(&es).print();
+
+    // Constant lookups do not receive any gradient.
+    let c = cg.const_lookup(&lp, 5);
+    c.print();
+}
@@ -1,14 +1,101 @@
 #![allow(non_snake_case)]
 extern crate dynet as dy;
 
-fn read_data(file_name: &str, data:&mut Vec<Vec<i32>>) -> u32 {
-    let mut num_tokens:u32 = 0;
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use dy::trainer::*;
+use dy::rnn::*;
+use dy::ops::*;
+
+static LAYERS:u32 = 2;
+static INPUT_DIM:u32 = 32;
+static HIDDEN_DIM:u32 = 64;
+static ITERATION:u32 = 10;
 
+/// Read one sentence per line, mapping each token to an id. The id 0 is used
+/// for the sentence boundary `<s>`.
+fn read_data(file_name: &str, vocab: &mut HashMap<String, u32>, data:&mut Vec<Vec<u32>>) -> u32 {
+    let mut num_tokens:u32 = 0;
+    let file = File::open(file_name).expect("Unable to open the training data");
+    for line in BufReader::new(file).lines() {
+        let line = line.unwrap();
+        let mut sent = vec![0];
+        for word in line.split_whitespace() {
+            let next_id = vocab.len() as u32;
+            sent.push(*vocab.entry(word.to_string()).or_insert(next_id));
+            num_tokens += 1;
+        }
+        sent.push(0);
+        data.push(sent);
+    }
     num_tokens
 }
 
 
 struct RNNLanguageModel {
-    
+    p_c: dy::LookupParameter,
+    p_R: dy::Parameter,
+    p_bias: dy::Parameter,
+    builder: SimpleRNNBuilder,
+}
+
+impl RNNLanguageModel {
+    fn new(pc: &dy::ParameterCollection, vocab_size: u32) -> Self {
+        RNNLanguageModel {
+            p_c: pc.add_default_lookup_param(&[vocab_size, INPUT_DIM]),
+            p_R: pc.add_default_param(&[vocab_size, HIDDEN_DIM]),
+            p_bias: pc.add_default_param(&[vocab_size]),
+            builder: SimpleRNNBuilder::new(LAYERS, INPUT_DIM, HIDDEN_DIM, pc),
+        }
+    }
+
+    /// Build the graph computing the loss of predicting each word of `sent`
+    /// from the previous ones.
+    fn build_lm_graph(&self, cg: &dy::ComputationGraph, sent: &[u32]) -> dy::Expression {
+        self.builder.new_graph(cg, true);
+        self.builder.start_new_sequence(None);
+        let R = cg.load_param(&self.p_R);
+        let bias = cg.load_param(&self.p_bias);
+
+        let mut losses = Vec::new();
+        for t in 0..sent.len() - 1 {
+            let x = cg.lookup(&self.p_c, sent[t]);
+            let h = self.builder.add_input(&x);
+            let scores = affine_transform(&[bias.clone(), R.clone(), h]);
+            losses.push(pickneglogsoftmax(&scores, sent[t + 1]));
+        }
+        sum(&losses)
+    }
 }
 
+fn main() {
+    dy::initialize();
+
+    let file_name = env::args().nth(1).expect("Usage: rnnlm <training data>");
+    let mut vocab = HashMap::new();
+    vocab.insert("<s>".to_string(), 0);
+    let mut data = Vec::new();
+    let num_tokens = read_data(&file_name, &mut vocab, &mut data);
+    println!("{} sentences, {} tokens, {} types", data.len(), num_tokens, vocab.len());
+
+    let pc = dy::ParameterCollection::new();
+    let lm = RNNLanguageModel::new(&pc, vocab.len() as u32);
+    let trainer = SimpleSGD::default(&pc);
+
+    // Each sentence also predicts its final boundary.
+    let num_predictions = (num_tokens as usize + data.len()) as f32;
+    for iter in 0..ITERATION {
+        let mut total_loss = 0.0;
+        for sent in &data {
+            let cg = dy::ComputationGraph::new();
+            let loss = lm.build_lm_graph(&cg, sent);
+            total_loss += cg.forward(&loss);
+            cg.backward(&loss);
+            trainer.update();
+        }
+        println!("iter{}: perplexity = {}", iter+1, (total_loss / num_predictions).exp());
+    }
+}
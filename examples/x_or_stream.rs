@@ -0,0 +1,52 @@
+#![allow(non_snake_case)]
+extern crate dynet as dy;
+
+use dy::trainer::*;
+use dy::ops::*;
+
+static HIDDEN_SIZE:u32 = 8;
+static ITERATION:u32 = 30;
+
+fn main() {
+    dy::initialize();
+
+    let pc = dy::ParameterCollection::new();
+
+    let p_W = pc.add_default_param(&[HIDDEN_SIZE, 2]);
+    let p_b = pc.add_default_param(&[HIDDEN_SIZE]);
+    let p_V = pc.add_default_param(&[1, HIDDEN_SIZE]);
+    let p_a = pc.add_default_param(&[1]);
+
+    let trainer = SimpleSGD::default(&pc);
+
+    // Build the graph once, with updatable inputs.
+    let cg = dy::ComputationGraph::new();
+
+    let W = cg.load_param(&p_W);
+    let b = cg.load_param(&p_b);
+    let V = cg.load_param(&p_V);
+    let a = cg.load_param(&p_a);
+
+    let x = cg.add_input_handle(&[0.0, 0.0], &[2]);
+    let y = cg.add_input_handle(&[0.0], &[1]);
+
+    let h = tanh(&(&(&W * x.expr()) + &b));
+    let y_pred = &(&V * &h) + &a;
+    let loss = squared_distance(&y_pred, y.expr());
+
+    // Stream the examples through the same graph.
+    for iter in 0..ITERATION {
+        let mut total_loss = 0.0;
+        for mi in 0..4 {
+            let x1 = (mi % 2) > 0;
+            let x2 = (mi / 2) % 2 > 0;
+            x.set_values(&[if x1 {1.0} else {-1.0}, if x2 {1.0} else {-1.0}]);
+            y.set_values(&[if x1 != x2 {1.0} else {-1.0}]);
+
+            total_loss += cg.forward(&loss);
+            cg.backward(&loss);
+            trainer.update();
+        }
+        println!("iter{}: loss = {}", iter+1, total_loss / 4.0);
+    }
+}
@@ -0,0 +1,173 @@
+//! Attention mechanisms over a sequence of encoder states.
+//!
+//! The encoder states are given as a `{d, T}` matrix whose `T` columns are
+//! the states of the positions of the sequence (for example the result of
+//! `ops::concatenate_cols` on the outputs of a `rnn::BiRnn`). All the
+//! expressions can be batched.
+
+use std::cell::RefCell;
+
+use super::{ParameterCollection, Parameter, ComputationGraph, Expression};
+use super::ops::{colwise_add, softmax, tanh, transpose};
+
+/// Value subtracted from the scores of the padded positions, so that they get
+/// a weight of zero after the softmax.
+const MASK_PENALTY: f32 = 1e9;
+
+/// Turn a mask of 1s (positions to attend to) and 0s (padded positions) into
+/// the values to add to the scores.
+pub(crate) fn mask_penalty(mask: &Expression) -> Expression {
+    &(mask + -1.0) * MASK_PENALTY
+}
+
+/// Interface shared by the attention mechanisms.
+pub trait Attention {
+    /// Load the parameters of the attention into the computation graph `cg`.
+    ///
+    /// Call this once for each new computation graph, before `attend`.
+    fn new_graph(&self, cg: &ComputationGraph);
+
+    /// Attend to the encoder states `states` (a `{d, T}` matrix) with the
+    /// vector `query`, and return the context vector (of dimension `{d}`) and
+    /// the attention weights (of dimension `{T}`).
+    ///
+    /// `mask` is an optional `{T}` vector which is 1 for the positions to
+    /// attend to and 0 for the padded ones.
+    fn attend(&self, states: &Expression, query: &Expression, mask: Option<&Expression>)
+        -> (Expression, Expression);
+}
+
+/// Compute the context vector and the weights from the scores of each
+/// position.
+fn weigh(states: &Expression, scores: Expression, mask: Option<&Expression>) -> (Expression, Expression) {
+    let scores = match mask {
+        Some(m) => &scores + &mask_penalty(m),
+        None => scores,
+    };
+    let weights = softmax(&scores);
+    (states * &weights, weights)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// DotAttention
+////////////////////////////////////////////////////////////////////////////////
+
+/// Dot-product attention: the score of each position is the dot product of
+/// its state with the query, optionally scaled.
+///
+/// The states and the query must have the same size.
+pub struct DotAttention {
+    scale: f32,
+}
+
+impl DotAttention {
+    /// Create a dot-product attention.
+    pub fn new() -> Self {
+        DotAttention{scale: 1.0}
+    }
+
+    /// Create a dot-product attention whose scores are scaled by
+    /// `1 / sqrt(dim)`, as described in
+    /// [Vaswani et al., 2017](https://arxiv.org/abs/1706.03762).
+    pub fn scaled(dim: u32) -> Self {
+        DotAttention{scale: 1.0 / (dim as f32).sqrt()}
+    }
+}
+
+impl Attention for DotAttention {
+    fn new_graph(&self, _cg: &ComputationGraph) {}
+
+    fn attend(&self, states: &Expression, query: &Expression, mask: Option<&Expression>)
+        -> (Expression, Expression)
+    {
+        let scores = &(&transpose(states) * query) * self.scale;
+        weigh(states, scores, mask)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// BilinearAttention
+////////////////////////////////////////////////////////////////////////////////
+
+/// Bilinear attention: the score of each position is `h^T * W * q`, where `h`
+/// is its state and `q` the query, as described in
+/// [Luong et al., 2015](https://arxiv.org/abs/1508.04025).
+pub struct BilinearAttention {
+    p_w: Parameter,
+    w: RefCell<Option<Expression>>,
+}
+
+impl BilinearAttention {
+    /// Create a bilinear attention for states of size `state_dim` and queries
+    /// of size `query_dim`, and add its parameters to `pc`.
+    pub fn new(pc: &ParameterCollection, state_dim: u32, query_dim: u32) -> Self {
+        BilinearAttention {
+            p_w: pc.add_default_param(&[state_dim, query_dim]),
+            w: RefCell::new(None),
+        }
+    }
+}
+
+impl Attention for BilinearAttention {
+    fn new_graph(&self, cg: &ComputationGraph) {
+        *self.w.borrow_mut() = Some(cg.load_param(&self.p_w));
+    }
+
+    fn attend(&self, states: &Expression, query: &Expression, mask: Option<&Expression>)
+        -> (Expression, Expression)
+    {
+        let w = self.w.borrow();
+        let w = w.as_ref().expect("Call new_graph before using the attention");
+        let scores = &transpose(states) * &(w * query);
+        weigh(states, scores, mask)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// MlpAttention
+////////////////////////////////////////////////////////////////////////////////
+
+/// MLP attention: the score of each position is `v^T * tanh(W_h * h + W_q * q)`,
+/// where `h` is its state and `q` the query, as described in
+/// [Bahdanau et al., 2015](https://arxiv.org/abs/1409.0473).
+pub struct MlpAttention {
+    p_w_h: Parameter,
+    p_w_q: Parameter,
+    p_v: Parameter,
+    params: RefCell<Option<(Expression, Expression, Expression)>>,
+}
+
+impl MlpAttention {
+    /// Create an MLP attention for states of size `state_dim` and queries of
+    /// size `query_dim`, with a hidden layer of size `attention_dim`, and add
+    /// its parameters to `pc`.
+    pub fn new(pc: &ParameterCollection, state_dim: u32, query_dim: u32, attention_dim: u32) -> Self {
+        MlpAttention {
+            p_w_h: pc.add_default_param(&[attention_dim, state_dim]),
+            p_w_q: pc.add_default_param(&[attention_dim, query_dim]),
+            p_v: pc.add_default_param(&[attention_dim]),
+            params: RefCell::new(None),
+        }
+    }
+}
+
+impl Attention for MlpAttention {
+    fn new_graph(&self, cg: &ComputationGraph) {
+        *self.params.borrow_mut() = Some((
+            cg.load_param(&self.p_w_h),
+            cg.load_param(&self.p_w_q),
+            cg.load_param(&self.p_v),
+        ));
+    }
+
+    fn attend(&self, states: &Expression, query: &Expression, mask: Option<&Expression>)
+        -> (Expression, Expression)
+    {
+        let params = self.params.borrow();
+        let &(ref w_h, ref w_q, ref v) = params.as_ref().expect("Call new_graph before using the attention");
+        let hidden = tanh(&colwise_add(&(w_h * states), &(w_q * query)));
+        // {T, a} * {a} gives {T} scores, the shape of the mask.
+        let scores = &transpose(&hidden) * v;
+        weigh(states, scores, mask)
+    }
+}
@@ -0,0 +1,242 @@
+//! User-defined operations implemented in Rust.
+//!
+//! Implement the `CustomOp` trait and use `custom` to add the operation to a
+//! computation graph. The operation then takes part in the forward and
+//! backward passes like any built-in operation.
+//!
+//! # Example
+//! ```
+//! use dynet::Dim;
+//! use dynet::custom::{CustomOp, TensorView, TensorViewMut};
+//!
+//! /// Componentwise cube.
+//! struct Cube;
+//!
+//! impl CustomOp for Cube {
+//!     fn dim(&self, xs: &[Dim]) -> Dim {
+//!         let d: Vec<u32> = (0..xs[0].ordre() as usize).map(|i| xs[0][i]).collect();
+//!         Dim::new(&d, xs[0].batch_size())
+//!     }
+//!
+//!     fn forward(&self, xs: &[TensorView], fx: &mut TensorViewMut) {
+//!         for (y, x) in fx.values_mut().iter_mut().zip(xs[0].values()) {
+//!             *y = x * x * x;
+//!         }
+//!     }
+//!
+//!     fn backward(&self, xs: &[TensorView], _fx: &TensorView, dedf: &TensorView,
+//!                 _i: usize, dedxi: &mut TensorViewMut) {
+//!         let x = xs[0].values();
+//!         for (j, g) in dedxi.values_mut().iter_mut().enumerate() {
+//!             *g += 3.0 * x[j] * x[j] * dedf.values()[j];
+//!         }
+//!     }
+//! }
+//! ```
+
+extern crate dynet_sys as dn;
+
+use std::cmp;
+use std::ffi::CString;
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+use std::slice;
+
+use super::{Expression, Dim};
+
+/// Read-only view of a tensor given to a `CustomOp`.
+///
+/// The values are in column-major format, with the batch dimension last.
+pub struct TensorView<'a> {
+    dim: &'a [u32],
+    batch_size: u32,
+    values: &'a [f32],
+}
+
+impl<'a> TensorView<'a> {
+    /// Get the dimension of the tensor, without the batch dimension.
+    pub fn dim(&self) -> &[u32] {
+        self.dim
+    }
+
+    /// Get the batch size of the tensor.
+    pub fn batch_size(&self) -> u32 {
+        self.batch_size
+    }
+
+    /// Get the values of the tensor.
+    pub fn values(&self) -> &[f32] {
+        self.values
+    }
+}
+
+/// Mutable view of a tensor given to a `CustomOp`.
+///
+/// The values are in column-major format, with the batch dimension last.
+pub struct TensorViewMut<'a> {
+    dim: &'a [u32],
+    batch_size: u32,
+    values: &'a mut [f32],
+}
+
+impl<'a> TensorViewMut<'a> {
+    /// Get the dimension of the tensor, without the batch dimension.
+    pub fn dim(&self) -> &[u32] {
+        self.dim
+    }
+
+    /// Get the batch size of the tensor.
+    pub fn batch_size(&self) -> u32 {
+        self.batch_size
+    }
+
+    /// Get the values of the tensor.
+    pub fn values_mut(&mut self) -> &mut [f32] {
+        self.values
+    }
+}
+
+/// An operation implemented in Rust.
+///
+/// Panicking in any of the methods aborts the process, since the methods are
+/// called from DyNet.
+pub trait CustomOp {
+    /// Get the name of the operation, as shown by
+    /// `ComputationGraph::print_graphviz`.
+    fn name(&self) -> String {
+        "custom".to_string()
+    }
+
+    /// Get the dimension of the result from the dimensions of the arguments.
+    ///
+    /// The result can have at most as many dimensions as DyNet supports (7 by
+    /// default), otherwise DyNet reports an error when building the graph.
+    fn dim(&self, xs: &[Dim]) -> Dim;
+
+    /// Compute the result `fx` from the arguments `xs`.
+    fn forward(&self, xs: &[TensorView], fx: &mut TensorViewMut);
+
+    /// Compute the gradient with respect to the argument `xs[i]`.
+    ///
+    /// `dedf` is the gradient with respect to the result `fx`. The gradient
+    /// with respect to `xs[i]` must be **added** to `dedxi`, which may already
+    /// contain the gradient coming from other nodes.
+    fn backward(&self, xs: &[TensorView], fx: &TensorView, dedf: &TensorView,
+                i: usize, dedxi: &mut TensorViewMut);
+
+    /// Whether the autobatcher may merge several nodes of this operation.
+    ///
+    /// When `true`, nodes of this operation whose arguments have the same
+    /// dimensions are computed together by a single call to `forward` (and
+    /// `backward`) on arguments concatenated along the batch dimension. Only
+    /// enable this if the batch elements are processed independently.
+    fn batchable(&self) -> bool {
+        false
+    }
+}
+
+// DyNet calls these for as long as the node exists, so they must outlive any
+// single call to `custom`.
+static CALLBACKS: dn::DN_CustomOpCallbacks = dn::DN_CustomOpCallbacks {
+    dim: Some(dim_trampoline),
+    forward: Some(forward_trampoline),
+    backward: Some(backward_trampoline),
+    drop: Some(drop_trampoline),
+};
+
+/// Add the custom operation `op` applied to `xs` to the computation graph.
+pub fn custom<O: CustomOp + 'static>(xs: &[Expression], op: O) -> Expression {
+    let c_name = CString::new(op.name()).unwrap();
+    let batchable = op.batchable();
+    let op: Box<Box<dyn CustomOp>> = Box::new(Box::new(op));
+    let mut xs_ptr:Vec<*mut dn::DN_Expression> = xs.iter().map(|x| x.inner).collect();
+    unsafe {
+        Expression{inner: dn::DN_CustomOp(
+            xs_ptr.as_mut_slice().as_mut_ptr(),
+            xs_ptr.len() as i32,
+            c_name.as_ptr(),
+            batchable,
+            Box::into_raw(op) as *mut c_void,
+            &CALLBACKS
+        )}
+    }
+}
+
+unsafe fn view<'a>(t: &'a dn::DN_TensorView) -> TensorView<'a> {
+    TensorView {
+        dim: slice::from_raw_parts(t.dims, t.nd as usize),
+        batch_size: t.bd,
+        values: slice::from_raw_parts(t.values, t.size),
+    }
+}
+
+unsafe fn view_mut<'a>(t: &'a mut dn::DN_TensorView) -> TensorViewMut<'a> {
+    TensorViewMut {
+        dim: slice::from_raw_parts(t.dims, t.nd as usize),
+        batch_size: t.bd,
+        values: slice::from_raw_parts_mut(t.values, t.size),
+    }
+}
+
+unsafe fn views<'a>(xs: *const dn::DN_TensorView, n: usize) -> Vec<TensorView<'a>> {
+    slice::from_raw_parts(xs, n).iter().map(|x| view(x)).collect()
+}
+
+// Copy `msg` into the buffer `buf` of `size` bytes, truncated if needed and
+// terminated by a null byte.
+unsafe fn write_error(buf: *mut c_char, size: usize, msg: &str) {
+    if size == 0 {
+        return;
+    }
+    let n = cmp::min(msg.len(), size - 1);
+    ptr::copy_nonoverlapping(msg.as_ptr() as *const c_char, buf, n);
+    *buf.add(n) = 0;
+}
+
+// `out_dims` has room for `max_nd` dimensions. If the dimension returned by the
+// operation does not fit, writes the reason to the buffer `err` of `err_size`
+// bytes and returns `false`, which makes DyNet raise an error with it.
+unsafe extern "C" fn dim_trampoline(
+    op: *mut c_void, xs: *const dn::DN_TensorView, n: usize,
+    out_dims: *mut u32, max_nd: u32, out_nd: *mut u32, out_bd: *mut u32,
+    err: *mut c_char, err_size: usize
+) -> bool {
+    let op = &*(op as *mut Box<dyn CustomOp>);
+    let xs: Vec<Dim> = slice::from_raw_parts(xs, n).iter()
+        .map(|x| Dim::new(slice::from_raw_parts(x.dims, x.nd as usize), x.bd))
+        .collect();
+    let d = op.dim(&xs);
+    if d.ordre() > max_nd {
+        let msg = format!("The custom operation {} returned a dimension with {} dimensions, but at most {} are supported.",
+                          op.name(), d.ordre(), max_nd);
+        write_error(err, err_size, &msg);
+        return false;
+    }
+    let out = slice::from_raw_parts_mut(out_dims, max_nd as usize);
+    for (i, o) in out.iter_mut().take(d.ordre() as usize).enumerate() {
+        *o = d[i];
+    }
+    *out_nd = d.ordre();
+    *out_bd = d.batch_size();
+    true
+}
+
+unsafe extern "C" fn forward_trampoline(
+    op: *mut c_void, xs: *const dn::DN_TensorView, n: usize, fx: *mut dn::DN_TensorView
+) {
+    let op = &*(op as *mut Box<dyn CustomOp>);
+    op.forward(&views(xs, n), &mut view_mut(&mut *fx));
+}
+
+unsafe extern "C" fn backward_trampoline(
+    op: *mut c_void, xs: *const dn::DN_TensorView, n: usize,
+    fx: *const dn::DN_TensorView, dedf: *const dn::DN_TensorView,
+    i: u32, dedxi: *mut dn::DN_TensorView
+) {
+    let op = &*(op as *mut Box<dyn CustomOp>);
+    op.backward(&views(xs, n), &view(&*fx), &view(&*dedf), i as usize, &mut view_mut(&mut *dedxi));
+}
+
+unsafe extern "C" fn drop_trampoline(op: *mut c_void) {
+    drop(Box::from_raw(op as *mut Box<dyn CustomOp>));
+}
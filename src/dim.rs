@@ -8,7 +8,7 @@ use std::ops::{Index};
 /// 
 /// 
 pub struct Dim {
-    inner: *mut dn::DN_Dim,
+    pub(crate) inner: *mut dn::DN_Dim,
     d: Vec<u32>
 }
 
@@ -1,6 +1,20 @@
 extern crate dynet_sys as dn;
 
-use super::{Parameter, Expression};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+use super::{Parameter, LookupParameter, Expression, Dim};
+use super::ops::{sum_batches, mean_batches};
+
+/// How the embeddings are combined by `ComputationGraph::embedding_bag`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BagMode {
+    /// Sum of the embeddings.
+    Sum,
+
+    /// Average of the embeddings.
+    Mean,
+}
 
 /// Computation graph structure.
 /// 
@@ -18,14 +32,36 @@ use super::{Parameter, Expression};
 /// 
 /// 
 pub struct ComputationGraph {
-    inner: *mut dn::DN_ComputationGraph
+    pub(crate) inner: *mut dn::DN_ComputationGraph,
+    // Buffers of the updatable inputs, they must outlive the graph.
+    input_buffers: RefCell<Vec<*mut dn::DN_FloatVector>>
 }
 
-
-impl_new!(ComputationGraph, DN_NewComputationGraph, "Create a new computation graph. Call this before building any new computation graph");
-impl_drop!(ComputationGraph, DN_DeleteComputationGraph);
+impl Drop for ComputationGraph {
+    fn drop(&mut self) {
+        unsafe {
+            dn::DN_DeleteComputationGraph(self.inner);
+            for buf in self.input_buffers.borrow().iter() {
+                dn::DN_DeleteFloatVector(*buf);
+            }
+        }
+    }
+}
 
 impl ComputationGraph {
+    /// Create a new computation graph. Call this before building any new
+    /// computation graph
+    pub fn new() -> Self {
+        unsafe {
+            let inner = dn::DN_NewComputationGraph();
+            assert!(!inner.is_null());
+            ComputationGraph {
+                inner: inner,
+                input_buffers: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
     /// Load parameters into the computation graph and returns an parameter
     /// `Expression` which can be used to build computation graph later.
     pub fn load_param(&self, p: &Parameter) -> Expression {
@@ -35,6 +71,72 @@ impl ComputationGraph {
     }
 
 
+    /// Load parameters into the computation graph as constants and returns an
+    /// `Expression` through which no gradient is propagated. This is
+    /// `const_parameter` in DyNet.
+    /// 
+    /// Use this for frozen sub-networks.
+    pub fn load_const_param(&self, p: &Parameter) -> Expression {
+        unsafe {
+            Expression{inner: dn::DN_LoadConstParamToCG(self.inner, p.inner)}
+        }
+    }
+
+    /// Look up the row `index` of `lp` and returns it as an `Expression`.
+    /// 
+    /// The gradient is propagated back to the looked-up row only.
+    pub fn lookup(&self, lp: &LookupParameter, index: u32) -> Expression {
+        unsafe {
+            Expression{inner: dn::DN_LookupToCG(self.inner, lp.inner, index)}
+        }
+    }
+
+    /// Look up the rows `indices` of `lp` and returns them as a single batched
+    /// `Expression` whose batch size is the length of `indices`.
+    pub fn lookup_batch(&self, lp: &LookupParameter, indices: &[u32]) -> Expression {
+        unsafe {
+            Expression{inner: dn::DN_LookupBatchToCG(
+                self.inner, lp.inner, indices.as_ptr(), indices.len()
+            )}
+        }
+    }
+
+    /// Same as `lookup`, but no gradient is propagated back to `lp`.
+    pub fn const_lookup(&self, lp: &LookupParameter, index: u32) -> Expression {
+        unsafe {
+            Expression{inner: dn::DN_ConstLookupToCG(self.inner, lp.inner, index)}
+        }
+    }
+
+    /// Same as `lookup_batch`, but no gradient is propagated back to `lp`.
+    pub fn const_lookup_batch(&self, lp: &LookupParameter, indices: &[u32]) -> Expression {
+        unsafe {
+            Expression{inner: dn::DN_ConstLookupBatchToCG(
+                self.inner, lp.inner, indices.as_ptr(), indices.len()
+            )}
+        }
+    }
+
+    /// Look up the rows `ids` of `lp` and combine them into a single vector
+    /// according to `mode`.
+    /// 
+    /// This is the usual bag-of-features representation and is more efficient
+    /// than combining the `lookup`s of each id one by one.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `ids` is empty.
+    pub fn embedding_bag(&self, lp: &LookupParameter, ids: &[u32], mode: BagMode) -> Expression {
+        if ids.is_empty() {
+            panic!("Unable to build an embedding bag from no ids.");
+        }
+        let es = self.lookup_batch(lp, ids);
+        match mode {
+            BagMode::Sum => sum_batches(&es),
+            BagMode::Mean => mean_batches(&es),
+        }
+    }
+
     /// Add input to the computation graph and return a an expression that
     /// represents a vector, matrix, or tensor input.The returned `Expression`
     /// object can be used to build computation graph later.
@@ -66,6 +168,79 @@ impl ComputationGraph {
         }
     }
     
+    /// Add a sparse input to the computation graph.
+    /// 
+    /// The input has the dimension `dim` and is filled with `default` except at
+    /// the positions `ids` (indices in column-major format), which take the
+    /// corresponding values in `vals`. This avoids building large dense
+    /// vectors for sparse indicator features.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `ids` and `vals` have different lengths, or if an id is not
+    /// smaller than the number of elements of `dim`.
+    /// 
+    /// # Example
+    /// ```
+    /// let cg = dynet::ComputationGraph::new();
+    /// 
+    /// // A 1000000-length indicator vector with three active features.
+    /// let x = cg.add_sparse_input(&[1000000], &[3, 42, 5000], &[1.0, 1.0, 1.0], 0.0);
+    /// ```
+    pub fn add_sparse_input(&self, dim: &[i64], ids: &[u32], vals: &[f32], default: f32) -> Expression {
+        if ids.len() != vals.len() {
+            panic!("The number of ids ({}) and values ({}) of a sparse input must be equal.", ids.len(), vals.len());
+        }
+        let size: i64 = dim.iter().product();
+        if let Some(id) = ids.iter().find(|&&id| id as i64 >= size) {
+            panic!("The id {} of a sparse input is out of range for {} elements.", id, size);
+        }
+        unsafe {
+            let dim_ptr = dn::DN_NewDimFromArray(dim.as_ptr(), dim.len(), 1);
+            let expr = Expression{inner: dn::DN_AddSparseInputToCG(
+                self.inner, dim_ptr, ids.as_ptr(), vals.as_ptr(), vals.len(), default
+            )};
+            dn::DN_DeleteDim(dim_ptr);
+            expr
+        }
+    }
+
+    /// Add an updatable input to the computation graph and return a handle to
+    /// it.
+    /// 
+    /// `vals` and `dim` are the same as in `add_input`. The values of the input
+    /// can later be replaced with `InputHandle::set_values`, so a graph built
+    /// once can be reused across a stream of examples without rebuilding it.
+    /// Call `forward` again after setting new values.
+    /// 
+    /// # Example
+    /// ```
+    /// let cg = dynet::ComputationGraph::new();
+    /// let x = cg.add_input_handle(&[0.0, 0.0], &[2]);
+    /// // ... build the graph from x.expr() ...
+    /// for example in &[[1.0, -1.0], [-1.0, 1.0]] {
+    ///     x.set_values(example);
+    ///     // cg.forward(&loss);
+    /// }
+    /// ```
+    pub fn add_input_handle<'a>(&'a self, vals: &[f32], dim: &[i64]) -> InputHandle<'a> {
+        unsafe {
+            let buf = dn::DN_NewFloatVector(vals.as_ptr(), vals.len());
+            self.input_buffers.borrow_mut().push(buf);
+            let dim_ptr = dn::DN_NewDimFromArray(dim.as_ptr(), dim.len(), 1);
+            let expr = Expression{inner: dn::DN_AddInputFromVectorToCG(
+                self.inner, dim_ptr, buf
+            )};
+            dn::DN_DeleteDim(dim_ptr);
+            InputHandle {
+                expr: expr,
+                buf: buf,
+                len: vals.len(),
+                _cg: PhantomData,
+            }
+        }
+    }
+
     ///  
     /// 
     /// The last dimension of the `dim` is used as the batch dimension.
@@ -81,6 +256,83 @@ impl ComputationGraph {
         }
     }
 
+    /// Add a scalar input to the computation graph.
+    pub fn scalar_input(&self, val: f32) -> Expression {
+        unsafe {
+            Expression{inner: dn::DN_AddScalarInputToCG(self.inner, val)}
+        }
+    }
+
+    /// Create an input full of zeros with the dimension (and batch size) `dim`.
+    /// 
+    /// Typically used for the initial state of recurrent networks.
+    pub fn zeros(&self, dim: &Dim) -> Expression {
+        unsafe {
+            Expression{inner: dn::DN_ZerosToCG(self.inner, dim.inner)}
+        }
+    }
+
+    /// Create an input full of ones with the dimension (and batch size) `dim`.
+    pub fn ones(&self, dim: &Dim) -> Expression {
+        unsafe {
+            Expression{inner: dn::DN_OnesToCG(self.inner, dim.inner)}
+        }
+    }
+
+    /// Create an input full of `val` with the dimension (and batch size) `dim`.
+    pub fn constant(&self, dim: &Dim, val: f32) -> Expression {
+        unsafe {
+            Expression{inner: dn::DN_ConstantToCG(self.inner, dim.inner, val)}
+        }
+    }
+
+    /// Create an input of dimension `dim` with values sampled from a normal
+    /// distribution of mean `mean` and standard deviation `stddev`.
+    /// 
+    /// Like all the random inputs, the values are drawn from DyNet's random
+    /// number generator and are reproducible with `reset_rand_seed`.
+    pub fn random_normal(&self, dim: &Dim, mean: f32, stddev: f32) -> Expression {
+        unsafe {
+            Expression{inner: dn::DN_RandomNormalToCG(self.inner, dim.inner, mean, stddev)}
+        }
+    }
+
+    /// Create an input of dimension `dim` with values sampled from a uniform
+    /// distribution over the interval `[left, right]`.
+    pub fn random_uniform(&self, dim: &Dim, left: f32, right: f32) -> Expression {
+        unsafe {
+            Expression{inner: dn::DN_RandomUniformToCG(self.inner, dim.inner, left, right)}
+        }
+    }
+
+    /// Create an input of dimension `dim` where each value is `scale` with
+    /// probability `p` and 0 otherwise.
+    /// 
+    /// Useful to build random masks.
+    pub fn random_bernoulli(&self, dim: &Dim, p: f32, scale: f32) -> Expression {
+        unsafe {
+            Expression{inner: dn::DN_RandomBernoulliToCG(self.inner, dim.inner, p, scale)}
+        }
+    }
+
+    /// Create an input of dimension `dim` with values sampled from a Gumbel
+    /// distribution of location `mu` and scale `beta`.
+    /// 
+    /// Useful for sampling with the Gumbel-max trick. DyNet only supports the
+    /// standard Gumbel distribution, so `mu` must be 0 and `beta` 1.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `mu` is not 0 or `beta` is not 1.
+    pub fn random_gumbel(&self, dim: &Dim, mu: f32, beta: f32) -> Expression {
+        if mu != 0.0 || beta != 1.0 {
+            panic!("Only the standard Gumbel distribution (mu = 0, beta = 1) is supported, but mu = {} and beta = {} were provided.", mu, beta);
+        }
+        unsafe {
+            Expression{inner: dn::DN_RandomGumbelToCG(self.inner, dim.inner, mu, beta)}
+        }
+    }
+
     /// Run complete forward pass from first node to given one, ignoring all 
     /// precomputed values.
     pub fn forward(&self, last: &Expression) -> f32 {
@@ -140,5 +392,33 @@ pub fn get_current_graph_id() -> u32 {
 }
 
 
+/// Handle to an input whose values can be updated, see
+/// `ComputationGraph::add_input_handle`.
+pub struct InputHandle<'a> {
+    expr: Expression,
+    buf: *mut dn::DN_FloatVector,
+    len: usize,
+    _cg: PhantomData<&'a ComputationGraph>,
+}
 
+impl<'a> InputHandle<'a> {
+    /// Get the `Expression` representing the input.
+    pub fn expr(&self) -> &Expression {
+        &self.expr
+    }
 
+    /// Replace the values of the input, in column-major format.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the length of `vals` differs from the length of the initial
+    /// values.
+    pub fn set_values(&self, vals: &[f32]) {
+        if vals.len() != self.len {
+            panic!("The input has {} values, but {} values were provided.", self.len, vals.len());
+        }
+        unsafe {
+            dn::DN_SetFloatVector(self.buf, vals.as_ptr(), vals.len());
+        }
+    }
+}
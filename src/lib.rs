@@ -3,7 +3,7 @@ extern crate dynet_sys as dn;
 use std::ffi::CString;
 use std::ffi::CStr;
 
-use std::ops::{Add, Mul};
+use std::ops::{Add, Sub, Mul};
 
 ////////////////////////////////////////////////////////////////////////////////
 // Macros
@@ -67,7 +67,12 @@ impl Parameter {
 // LookupParameter
 ////////////////////////////////////////////////////////////////////////////////
 
+/// LookupParameters represent a table of parameters, such as word embeddings.
 /// 
+/// Use `ParameterCollection`'s method `add_lookup_param` to create a new
+/// `LookupParameter`, then `ComputationGraph`'s method `lookup` to load the
+/// rows of the table into a computation graph. Only the rows that were looked
+/// up are updated by the trainer.
 pub struct LookupParameter {
     inner: *mut dn::DN_LookupParameter
 }
@@ -75,7 +80,36 @@ pub struct LookupParameter {
 impl_drop!(LookupParameter, DN_DeleteLookupParameter);
 
 impl LookupParameter {
+    /// Initialize the row `index` of the table with the values `vals`, e.g. a
+    /// pretrained embedding.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `vals` is empty.
+    pub fn initialize(&self, index: u32, vals: &[f32]) {
+        if vals.is_empty() {
+            panic!("Unable to initialize a lookup parameter with empty values.");
+        }
+        unsafe {
+            dn::DN_InitializeLookupParameter(self.inner, index, vals.as_ptr(), vals.len());
+        }
+    }
 
+    /// Set whether the lookup parameter is updated by the trainer.
+    /// 
+    /// Use this to freeze pretrained embeddings.
+    pub fn set_updated(&self, updated: bool) {
+        unsafe {
+            dn::DN_SetLookupParameterUpdated(self.inner, updated);
+        }
+    }
+
+    /// Whether the lookup parameter is updated by the trainer.
+    pub fn is_updated(&self) -> bool {
+        unsafe {
+            dn::DN_IsLookupParameterUpdated(self.inner)
+        }
+    }
 }
 
 
@@ -385,6 +419,15 @@ impl<'a> Add for &'a Expression {
     }
 }
 
+impl<'a> Sub for &'a Expression {
+    type Output = Expression;
+    fn sub(self, rhs: Self) -> Expression {
+        unsafe {
+            Expression{inner: dn::DN_Subtract(self.inner, rhs.inner)}
+        }
+    }
+}
+
 impl<'a> Mul for &'a Expression {
     type Output = Expression;
     fn mul(self, rhs: Self) -> Expression {
@@ -394,6 +437,32 @@ impl<'a> Mul for &'a Expression {
     }
 }
 
+impl<'a> Add<f32> for &'a Expression {
+    type Output = Expression;
+    fn add(self, rhs: f32) -> Expression {
+        unsafe {
+            Expression{inner: dn::DN_AddConst(self.inner, rhs)}
+        }
+    }
+}
+
+impl<'a> Mul<f32> for &'a Expression {
+    type Output = Expression;
+    fn mul(self, rhs: f32) -> Expression {
+        unsafe {
+            Expression{inner: dn::DN_MultiplyConst(self.inner, rhs)}
+        }
+    }
+}
+
+impl Clone for Expression {
+    fn clone(&self) -> Self {
+        unsafe {
+            Expression{inner: dn::DN_CopyExpression(self.inner)}
+        }
+    }
+}
+
 impl Expression {
     pub fn print(&self) {
         unsafe {
@@ -401,6 +470,19 @@ impl Expression {
             dn::DN_PrintTensor(tensor);
         }
     }
+
+    /// Get the value of the expression as a vector, in column-major format.
+    ///
+    /// The part of the computation graph needed to compute this expression is
+    /// evaluated if it has not been already.
+    pub fn value(&self) -> Vec<f32> {
+        unsafe {
+            let tensor = dn::DN_GetExprValue(self.inner);
+            let mut vals = vec![0.0; dn::DN_TensorSize(tensor)];
+            dn::DN_CopyTensorToArray(tensor, vals.as_mut_ptr());
+            vals
+        }
+    }
 }
 
 mod dim;
@@ -410,11 +492,26 @@ mod param_init;
 pub use param_init::{ParamInit};
 
 mod graph;
-pub use graph::{ComputationGraph, get_current_graph_id};
+pub use graph::{ComputationGraph, InputHandle, BagMode, get_current_graph_id};
 
 mod init;
 pub use init::{initialize, reset_rand_seed};
 
+mod mode;
+pub use mode::{set_training, is_training};
+
 pub mod trainer;
 
 pub mod ops;
+
+pub mod norm;
+
+pub mod custom;
+
+pub mod rnn;
+
+pub mod attention;
+
+pub mod transformer;
+
+pub mod scheduler;
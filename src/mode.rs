@@ -0,0 +1,33 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static TRAINING: AtomicBool = AtomicBool::new(true);
+
+/// Switch between training and evaluation mode.
+///
+/// Stochastic operations such as `ops::dropout` or `ops::noise` are only
+/// applied in training mode; in evaluation mode they return their input
+/// unchanged. This lets the same model code be used for training and for
+/// evaluation without manual branching. DyNet starts in training mode.
+///
+/// The mode is global to the process and is read when an expression is
+/// built, so switch it before building the computation graph.
+///
+/// # Examples
+/// ```
+/// extern crate dynet as dy;
+///
+/// fn main() {
+///     dy::initialize();
+///     // ... training ...
+///     dy::set_training(false);
+///     // ... evaluation ...
+/// }
+/// ```
+pub fn set_training(training: bool) {
+    TRAINING.store(training, Ordering::SeqCst);
+}
+
+/// Whether dynet is in training mode. See `set_training`.
+pub fn is_training() -> bool {
+    TRAINING.load(Ordering::SeqCst)
+}
@@ -0,0 +1,111 @@
+//! Normalization layers whose parameters live in a `ParameterCollection`.
+//!
+//! Stateless normalizations such as `ops::layer_norm` and `ops::weight_norm`
+//! are in the `ops` module.
+
+use std::cell::RefCell;
+
+use super::{ParameterCollection, Parameter, ComputationGraph, Expression, ParamInit, is_training};
+use super::ops::{cmult, cdiv, square, sqrt, mean_batches};
+
+/// Batch normalization, as described in
+/// [Ioffe and Szegedy, 2015](https://arxiv.org/abs/1502.03167).
+///
+/// In training mode (see `set_training`) the input is normalized with the mean
+/// and the variance of the current mini-batch, and running estimates of both
+/// are updated. In evaluation mode the running estimates are used instead, so
+/// the output does not depend on the other elements of the batch.
+///
+/// The running statistics are kept in Rust and are not saved with the
+/// `ParameterCollection`.
+pub struct BatchNorm {
+    gamma: Parameter,
+    beta: Parameter,
+    dim: u32,
+    momentum: f32,
+    epsilon: f32,
+    running_mean: RefCell<Vec<f32>>,
+    running_var: RefCell<Vec<f32>>,
+}
+
+impl BatchNorm {
+    /// Create a batch normalization layer for vectors of size `dim` and add its
+    /// gain and bias to `pc`.
+    ///
+    /// The running statistics are updated as
+    /// `running = momentum * running + (1 - momentum) * batch`, and `epsilon`
+    /// is added to the variance for numerical stability. Common values are
+    /// `momentum=0.9` and `epsilon=1e-5`.
+    pub fn new(pc: &ParameterCollection, dim: u32, momentum: f32, epsilon: f32) -> Self {
+        BatchNorm {
+            gamma: pc.add_param(&[dim], ParamInit::Const(1.0), "bn-gamma"),
+            beta: pc.add_param(&[dim], ParamInit::Const(0.0), "bn-beta"),
+            dim: dim,
+            momentum: momentum,
+            epsilon: epsilon,
+            running_mean: RefCell::new(vec![0.0; dim as usize]),
+            running_var: RefCell::new(vec![1.0; dim as usize]),
+        }
+    }
+
+    /// Normalize the batched vector `x` of dimension `{dim}`.
+    ///
+    /// In training mode the batch statistics are evaluated while building the
+    /// graph, so the part of `cg` that computes `x` is run forward by this
+    /// call, and the running statistics are updated each time it is called,
+    /// even if the graph is never trained on. Switch to evaluation mode to
+    /// apply the layer without changing them.
+    ///
+    /// # Panics
+    ///
+    /// Panics in training mode if the batch size of `x` is 1, since the batch
+    /// variance would then be 0.
+    pub fn apply(&self, cg: &ComputationGraph, x: &Expression) -> Expression {
+        let normalized = if is_training() {
+            let batch_size = x.value().len() / self.dim as usize;
+            if batch_size < 2 {
+                panic!("Batch normalization in training mode needs a batch of at least 2 elements, but the batch size is {}.", batch_size);
+            }
+            let mean = mean_batches(x);
+            let centered = x - &mean;
+            let var = mean_batches(&square(&centered));
+            self.update_running_stats(&mean.value(), &var.value());
+
+            // The gradient flows through the batch statistics, so the
+            // inverse standard deviation is computed in the graph.
+            let eps = cg.add_input(&vec![self.epsilon; self.dim as usize], &[self.dim as i64]);
+            let std = sqrt(&(&var + &eps));
+            cdiv(&centered, &std)
+        } else {
+            let mean = cg.add_input(&self.running_mean.borrow(), &[self.dim as i64]);
+            let inv_std: Vec<f32> = self.running_var.borrow().iter()
+                .map(|v| 1.0 / (v + self.epsilon).sqrt())
+                .collect();
+            let inv_std = cg.add_input(&inv_std, &[self.dim as i64]);
+            cmult(&(x - &mean), &inv_std)
+        };
+        let gamma = cg.load_param(&self.gamma);
+        let beta = cg.load_param(&self.beta);
+        &cmult(&normalized, &gamma) + &beta
+    }
+
+    /// Get the running mean estimate.
+    pub fn running_mean(&self) -> Vec<f32> {
+        self.running_mean.borrow().clone()
+    }
+
+    /// Get the running variance estimate.
+    pub fn running_var(&self) -> Vec<f32> {
+        self.running_var.borrow().clone()
+    }
+
+    fn update_running_stats(&self, mean: &[f32], var: &[f32]) {
+        let m = self.momentum;
+        for (r, v) in self.running_mean.borrow_mut().iter_mut().zip(mean) {
+            *r = m * *r + (1.0 - m) * v;
+        }
+        for (r, v) in self.running_var.borrow_mut().iter_mut().zip(var) {
+            *r = m * *r + (1.0 - m) * v;
+        }
+    }
+}
@@ -1,6 +1,6 @@
 extern crate dynet_sys as dn;
 
-use super::Expression;
+use super::{Expression, is_training};
 
 pub fn tanh(x: &Expression) -> Expression {
     unsafe {
@@ -8,6 +8,13 @@ pub fn tanh(x: &Expression) -> Expression {
     }
 }
 
+/// Rectified linear unit: componentwise `max(x, 0)`.
+pub fn rectify(x: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Rectify(x.inner)}
+    }
+}
+
 
 pub fn squared_distance(x: &Expression, y: &Expression) -> Expression {
     unsafe {
@@ -29,4 +36,563 @@ pub fn sum_batches(x: &Expression) -> Expression {
     unsafe {
         Expression{inner: dn::DN_SumBatches(x.inner)}
     }
-}
\ No newline at end of file
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Linear algebra
+////////////////////////////////////////////////////////////////////////////////
+
+/// Compute `xs[0] + xs[1] * xs[2] + xs[3] * xs[4] + ...` in a single node.
+///
+/// This is the fused form of a dense layer `b + W * x` and is more efficient
+/// than building the same expression with `+` and `*`. The length of `xs` must
+/// be odd.
+///
+/// # Panics
+///
+/// Panics if the length of `xs` is even.
+pub fn affine_transform(xs: &[Expression]) -> Expression {
+    if xs.len() % 2 == 0 {
+        panic!("affine_transform expects an odd number of expressions, but {} were provided.", xs.len());
+    }
+    let mut xs_ptr:Vec<*mut dn::DN_Expression> = xs.iter().map(|x| x.inner).collect();
+    unsafe {
+        Expression{
+            inner: dn::DN_AffineTransform(xs_ptr.as_mut_slice().as_mut_ptr(), xs_ptr.len() as i32)
+        }
+    }
+}
+
+/// Dot product of two vectors.
+pub fn dot_product(x: &Expression, y: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_DotProduct(x.inner, y.inner)}
+    }
+}
+
+/// Componentwise multiplication of `x` and `y`.
+///
+/// The dimensions of `x` and `y` are broadcast against each other.
+pub fn cmult(x: &Expression, y: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Cmult(x.inner, y.inner)}
+    }
+}
+
+/// Componentwise division of `x` by `y`.
+///
+/// The dimensions of `x` and `y` are broadcast against each other.
+pub fn cdiv(x: &Expression, y: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Cdiv(x.inner, y.inner)}
+    }
+}
+
+/// Add the column vector `bias` to every column of the matrix `x`.
+pub fn colwise_add(x: &Expression, bias: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_ColwiseAdd(x.inner, bias.inner)}
+    }
+}
+
+/// Inverse of the square matrix `x`.
+pub fn inverse(x: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Inverse(x.inner)}
+    }
+}
+
+/// Log of the determinant of the square matrix `x`.
+pub fn logdet(x: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Logdet(x.inner)}
+    }
+}
+
+/// Trace of the matrix product `x * transpose(y)`.
+pub fn trace_of_product(x: &Expression, y: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_TraceOfProduct(x.inner, y.inner)}
+    }
+}
+
+/// Contract the order-3 tensor `x` (of dimension `{i, j, k}`) with the vector
+/// `y` (of dimension `{k}`) along the last axis, giving an `{i, j}` matrix.
+pub fn contract3d_1d(x: &Expression, y: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Contract3d1d(x.inner, y.inner)}
+    }
+}
+
+/// Contract the order-3 tensor `x` (of dimension `{i, j, k}`) with the vectors
+/// `y` (of dimension `{k}`) and `z` (of dimension `{j}`), giving a vector of
+/// dimension `{i}`.
+pub fn contract3d_1d_1d(x: &Expression, y: &Expression, z: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Contract3d1d1d(x.inner, y.inner, z.inner)}
+    }
+}
+
+/// Sum of every `n` consecutive columns of `x`, as used by the ngram
+/// convolution of [Kalchbrenner et al., 2014](https://arxiv.org/abs/1404.2188).
+///
+/// The column `i` of the result is the sum of the columns `i` to `i + n - 1`
+/// of `x`, so a `{d, T}` input gives a `{d, T - n + 1}` result.
+pub fn kmh_ngram(x: &Expression, n: u32) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_KmhNgram(x.inner, n)}
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Convolution and pooling
+////////////////////////////////////////////////////////////////////////////////
+
+/// Padding modes for two-dimensional convolution and pooling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Padding {
+    /// No padding: the filter only visits positions where it fits entirely
+    /// inside the input, so the output shrinks by the size of the filter.
+    Valid,
+
+    /// Zero padding so that, with a stride of 1, the output has the same height
+    /// and width as the input.
+    Same,
+}
+
+impl Padding {
+    fn is_valid(self) -> bool {
+        self == Padding::Valid
+    }
+}
+
+fn check_window(name: &str, window: &[u32]) {
+    if window.len() != 2 {
+        panic!("The length of {} must be 2, but the provided {} is {:?}.", name, name, window);
+    }
+}
+
+/// Two-dimensional convolution of the input `x` with the filters `f`.
+///
+/// `x` has the dimension `{H, W, C_in}` (batched or not), where `H` is the
+/// height, `W` the width and `C_in` the number of input channels. As for every
+/// input of `ComputationGraph::add_input`, values are laid out in column-major
+/// format, i.e. the height index varies fastest, then the width, then the
+/// channel.
+///
+/// `f` has the dimension `{H_f, W_f, C_in, C_out}` and `stride` contains the
+/// vertical and horizontal strides. The result has the dimension
+/// `{H', W', C_out}`.
+///
+/// # Panics
+///
+/// Panics if the length of `stride` is not 2.
+pub fn conv2d(x: &Expression, f: &Expression, stride: &[u32], padding: Padding) -> Expression {
+    check_window("stride", stride);
+    unsafe {
+        Expression{inner: dn::DN_Conv2d(
+            x.inner, f.inner, stride.as_ptr(), stride.len(), padding.is_valid()
+        )}
+    }
+}
+
+/// Two-dimensional max pooling of the input `x`.
+///
+/// `x` has the dimension `{H, W, C}` with the same layout as in `conv2d`.
+/// `ksize` contains the height and width of the pooling window and `stride`
+/// the vertical and horizontal strides. Pooling is done on each channel
+/// separately.
+///
+/// # Panics
+///
+/// Panics if the length of `ksize` or `stride` is not 2.
+pub fn maxpooling2d(x: &Expression, ksize: &[u32], stride: &[u32], padding: Padding) -> Expression {
+    check_window("ksize", ksize);
+    check_window("stride", stride);
+    unsafe {
+        Expression{inner: dn::DN_Maxpooling2d(
+            x.inner, ksize.as_ptr(), ksize.len(), stride.as_ptr(), stride.len(), padding.is_valid()
+        )}
+    }
+}
+
+/// Narrow one-dimensional convolution along the columns.
+///
+/// `x` is a `{d, n}` matrix where each of the `n` columns is a `d` dimensional
+/// item of the sequence (e.g. a word embedding) and `f` is a `{d, m}` filter.
+/// Each row is convolved separately and the result is a `{d, n - m + 1}`
+/// matrix.
+pub fn conv1d_narrow(x: &Expression, f: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Conv1dNarrow(x.inner, f.inner)}
+    }
+}
+
+/// Wide one-dimensional convolution along the columns.
+///
+/// Same as `conv1d_narrow` but the sequence is padded with zeros, so the result
+/// is a `{d, n + m - 1}` matrix.
+pub fn conv1d_wide(x: &Expression, f: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Conv1dWide(x.inner, f.inner)}
+    }
+}
+
+/// Narrow one-dimensional filtering along the columns.
+///
+/// `x` is a `{d, n}` matrix and `f` a `{d, m}` filter. Unlike `conv1d_narrow`,
+/// the element-wise products of `f` and each window of `m` columns of `x` are
+/// summed over all the rows, giving a `{1, n - m + 1}` matrix.
+pub fn filter1d_narrow(x: &Expression, f: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Filter1dNarrow(x.inner, f.inner)}
+    }
+}
+
+/// Keep the `k` largest values along the dimension `d` of `x`, in their
+/// original order.
+///
+/// With `d=1` on a `{d, n}` sentence matrix, this is the k-max pooling of
+/// [Kalchbrenner et al., 2014](https://arxiv.org/abs/1404.2188) and gives a
+/// `{d, k}` matrix.
+pub fn kmax_pooling(x: &Expression, k: u32, d: u32) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_KmaxPooling(x.inner, k, d)}
+    }
+}
+
+/// Sum every `nrows` consecutive rows of `x`.
+///
+/// A `{d, n}` matrix gives a `{d / nrows, n}` matrix.
+pub fn fold_rows(x: &Expression, nrows: u32) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_FoldRows(x.inner, nrows)}
+    }
+}
+
+/// Average of the columns of `x`.
+///
+/// A `{d, n}` matrix gives a `{d}` vector.
+pub fn average_cols(x: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_AverageCols(x.inner)}
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Dropout and noise
+//
+// These operations are only applied in training mode, see `set_training`.
+// In evaluation mode they return their input unchanged.
+////////////////////////////////////////////////////////////////////////////////
+
+/// Dropout: set each element of `x` to zero with probability `p`.
+///
+/// The remaining elements are scaled by `1 / (1 - p)` so that no rescaling is
+/// needed at evaluation time.
+pub fn dropout(x: &Expression, p: f32) -> Expression {
+    if !is_training() {
+        return x.clone();
+    }
+    unsafe {
+        Expression{inner: dn::DN_Dropout(x.inner, p)}
+    }
+}
+
+/// Dropout along the dimension `d`: slices of `x` orthogonal to `d` are set to
+/// zero together with probability `p`.
+///
+/// For example, with `d=1` on a `{d, n}` sentence matrix whole rows (features)
+/// are dropped across the sequence.
+pub fn dropout_dim(x: &Expression, d: u32, p: f32) -> Expression {
+    if !is_training() {
+        return x.clone();
+    }
+    unsafe {
+        Expression{inner: dn::DN_DropoutDim(x.inner, d, p)}
+    }
+}
+
+/// Dropout of whole batch elements of `x` with probability `p`.
+pub fn dropout_batch(x: &Expression, p: f32) -> Expression {
+    if !is_training() {
+        return x.clone();
+    }
+    unsafe {
+        Expression{inner: dn::DN_DropoutBatch(x.inner, p)}
+    }
+}
+
+/// Block dropout: set all the elements of `x` to zero together with
+/// probability `p`.
+pub fn block_dropout(x: &Expression, p: f32) -> Expression {
+    if !is_training() {
+        return x.clone();
+    }
+    unsafe {
+        Expression{inner: dn::DN_BlockDropout(x.inner, p)}
+    }
+}
+
+/// Add gaussian noise with standard deviation `stddev` to `x`.
+pub fn noise(x: &Expression, stddev: f32) -> Expression {
+    if !is_training() {
+        return x.clone();
+    }
+    unsafe {
+        Expression{inner: dn::DN_Noise(x.inner, stddev)}
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Normalization
+////////////////////////////////////////////////////////////////////////////////
+
+/// Layer normalization of the vector `x` with gain `g` and bias `b`, as
+/// described in [Ba et al., 2016](https://arxiv.org/abs/1607.06450).
+///
+/// `x` is normalized to zero mean and unit variance, then scaled
+/// componentwise by `g` and shifted by `b`. `x`, `g` and `b` must have the
+/// same dimension.
+pub fn layer_norm(x: &Expression, g: &Expression, b: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_LayerNorm(x.inner, g.inner, b.inner)}
+    }
+}
+
+/// Weight normalization of the parameter `w` with the scalar gain `g`, as
+/// described in [Salimans and Kingma, 2016](https://arxiv.org/abs/1602.07868).
+///
+/// The result is `g * w / ||w||`.
+pub fn weight_norm(w: &Expression, g: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_WeightNorm(w.inner, g.inner)}
+    }
+}
+
+/// Componentwise square of `x`.
+pub fn square(x: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Square(x.inner)}
+    }
+}
+
+/// Componentwise square root of `x`.
+pub fn sqrt(x: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Sqrt(x.inner)}
+    }
+}
+
+/// Average of `x` over the batch elements.
+pub fn mean_batches(x: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_MeanBatches(x.inner)}
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Gradient control
+////////////////////////////////////////////////////////////////////////////////
+
+/// Identity in the forward pass, but no gradient is propagated back through
+/// `x`.
+pub fn nobackprop(x: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Nobackprop(x.inner)}
+    }
+}
+
+/// Identity in the forward pass, but the gradient is negated in the backward
+/// pass, as in the gradient reversal layer of
+/// [Ganin and Lempitsky, 2015](https://arxiv.org/abs/1409.7495).
+pub fn flip_gradient(x: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_FlipGradient(x.inner)}
+    }
+}
+
+/// Identity in the forward pass, but the gradient is multiplied by `lambda` in
+/// the backward pass.
+pub fn scale_gradient(x: &Expression, lambda: f32) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_ScaleGradient(x.inner, lambda)}
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Comparison and selection
+////////////////////////////////////////////////////////////////////////////////
+
+/// Componentwise maximum of `x` and `y`.
+pub fn max(x: &Expression, y: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Max(x.inner, y.inner)}
+    }
+}
+
+/// Componentwise minimum of `x` and `y`.
+pub fn min(x: &Expression, y: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Min(x.inner, y.inner)}
+    }
+}
+
+/// Componentwise maximum over the expressions `xs`, which must all have the
+/// same dimension.
+pub fn emax(xs: &[Expression]) -> Expression {
+    let mut xs_ptr:Vec<*mut dn::DN_Expression> = xs.iter().map(|x| x.inner).collect();
+    unsafe {
+        Expression{
+            inner: dn::DN_Emax(xs_ptr.as_mut_slice().as_mut_ptr(), xs_ptr.len() as i32)
+        }
+    }
+}
+
+/// Componentwise minimum over the expressions `xs`, which must all have the
+/// same dimension.
+pub fn emin(xs: &[Expression]) -> Expression {
+    let mut xs_ptr:Vec<*mut dn::DN_Expression> = xs.iter().map(|x| x.inner).collect();
+    unsafe {
+        Expression{
+            inner: dn::DN_Emin(xs_ptr.as_mut_slice().as_mut_ptr(), xs_ptr.len() as i32)
+        }
+    }
+}
+
+/// How the gradient is propagated through non-differentiable operations such
+/// as `argmax`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientMode {
+    /// No gradient is propagated.
+    Zero,
+
+    /// The gradient is propagated as if the operation was the identity, as in
+    /// the straight-through estimator of
+    /// [Bengio et al., 2013](https://arxiv.org/abs/1308.3432).
+    StraightThrough,
+}
+
+/// One-hot vector of the same dimension as the vector `x`, with a 1 at the
+/// position of the largest value of `x`.
+///
+/// The gradient is propagated according to `mode`.
+pub fn argmax(x: &Expression, mode: GradientMode) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Argmax(x.inner, mode == GradientMode::StraightThrough)}
+    }
+}
+
+/// Get the `k` largest values of the vector `x` and their indices, in
+/// decreasing order of value.
+///
+/// This evaluates `x`, so the part of the computation graph needed to compute
+/// it is run forward. If `x` has fewer than `k` values, all of them are
+/// returned. NaN values are ranked above all the others.
+///
+/// # Panics
+///
+/// Panics if `x` is batched, since the values of all the batch elements would
+/// be ranked together.
+pub fn topk(x: &Expression, k: usize) -> (Vec<f32>, Vec<u32>) {
+    let batch_size = unsafe { dn::DN_TensorBatchElems(dn::DN_GetExprValue(x.inner)) };
+    if batch_size != 1 {
+        panic!("topk expects an unbatched expression, but the batch size is {}.", batch_size);
+    }
+    let vals = x.value();
+    let mut ids: Vec<usize> = (0..vals.len()).collect();
+    ids.sort_by(|&i, &j| vals[j].total_cmp(&vals[i]));
+    ids.truncate(k);
+    (ids.iter().map(|&i| vals[i]).collect(), ids.iter().map(|&i| i as u32).collect())
+}
+
+/// Select componentwise between `on_true` and `on_false` according to `mask`.
+///
+/// `mask` should only contain 1s (select `on_true`) and 0s (select
+/// `on_false`); it is broadcast like in `cmult`. The gradient is propagated to
+/// the selected values only.
+pub fn select(mask: &Expression, on_true: &Expression, on_false: &Expression) -> Expression {
+    &cmult(mask, &(on_true - on_false)) + on_false
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Loss functions
+////////////////////////////////////////////////////////////////////////////////
+
+/// Negative log of the softmax of the vector `x`, picked at the position `v`.
+///
+/// This is the usual cross-entropy loss of a classifier whose scores are `x`
+/// and whose correct class is `v`. It is more efficient and numerically stable
+/// than computing the softmax and the log separately.
+pub fn pickneglogsoftmax(x: &Expression, v: u32) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_PickNegLogSoftmax(x.inner, v)}
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Shape manipulation
+////////////////////////////////////////////////////////////////////////////////
+
+/// Concatenate the expressions `xs` along the first dimension (rows).
+///
+/// For example, vectors of dimension `{a}` and `{b}` give a vector of
+/// dimension `{a + b}`.
+pub fn concatenate(xs: &[Expression]) -> Expression {
+    let mut xs_ptr:Vec<*mut dn::DN_Expression> = xs.iter().map(|x| x.inner).collect();
+    unsafe {
+        Expression{
+            inner: dn::DN_Concatenate(xs_ptr.as_mut_slice().as_mut_ptr(), xs_ptr.len() as i32)
+        }
+    }
+}
+
+/// Concatenate the expressions `xs` along the second dimension (columns).
+///
+/// For example, `n` vectors of dimension `{d}` give a `{d, n}` matrix.
+pub fn concatenate_cols(xs: &[Expression]) -> Expression {
+    let mut xs_ptr:Vec<*mut dn::DN_Expression> = xs.iter().map(|x| x.inner).collect();
+    unsafe {
+        Expression{
+            inner: dn::DN_ConcatenateCols(xs_ptr.as_mut_slice().as_mut_ptr(), xs_ptr.len() as i32)
+        }
+    }
+}
+
+/// Pick the element `v` of `x` along the dimension `d`.
+///
+/// For example, with `d=1` on a `{n, m}` matrix, this gives the column `v` as
+/// a vector of dimension `{n}`.
+pub fn pick(x: &Expression, v: u32, d: u32) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Pick(x.inner, v, d)}
+    }
+}
+
+/// Transpose of the matrix `x`.
+pub fn transpose(x: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Transpose(x.inner)}
+    }
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Probabilities
+////////////////////////////////////////////////////////////////////////////////
+
+/// Softmax of each column of `x`.
+///
+/// For a vector, the result is a probability distribution.
+pub fn softmax(x: &Expression) -> Expression {
+    unsafe {
+        Expression{inner: dn::DN_Softmax(x.inner)}
+    }
+}
@@ -0,0 +1,144 @@
+use super::super::{ComputationGraph, Expression};
+use super::super::ops::{cmult, concatenate, select};
+use super::RnnBuilder;
+
+/// Result of `BiRnn::transduce`.
+pub struct BiRnnOutput {
+    /// For each position, the concatenation of the outputs of the forward and
+    /// backward builders. Padded positions are zero.
+    pub states: Vec<Expression>,
+
+    /// Final state (as given by `RnnBuilder::final_s`) of the forward builder,
+    /// after the last non-padded position.
+    pub final_fwd: Vec<Expression>,
+
+    /// Final state (as given by `RnnBuilder::final_s`) of the backward builder,
+    /// after the first position.
+    pub final_bwd: Vec<Expression>,
+}
+
+/// Bidirectional sequence encoder.
+///
+/// Runs a forward builder from the first position to the last one and a
+/// backward builder from the last position to the first one, and concatenates
+/// their outputs at each position.
+///
+/// # Example
+/// ```
+/// use dynet::rnn::{BiRnn, VanillaLSTMBuilder};
+///
+/// let pc = dynet::ParameterCollection::new();
+/// let birnn = BiRnn::new(
+///     VanillaLSTMBuilder::new(1, 10, 20, &pc, false),
+///     VanillaLSTMBuilder::new(1, 10, 20, &pc, false),
+/// );
+///
+/// let cg = dynet::ComputationGraph::new();
+/// birnn.new_graph(&cg, true);
+/// let xs: Vec<_> = (0..5).map(|_| cg.add_input(&[0.0; 10], &[10])).collect();
+/// let out = birnn.transduce(&xs, None);
+/// assert_eq!(5, out.states.len());
+/// ```
+pub struct BiRnn<B: RnnBuilder> {
+    fwd: B,
+    bwd: B,
+}
+
+impl<B: RnnBuilder> BiRnn<B> {
+    /// Create a bidirectional encoder from a forward and a backward builder.
+    ///
+    /// The two builders must take inputs of the same size. The size of the
+    /// states is the sum of their hidden sizes.
+    pub fn new(fwd: B, bwd: B) -> Self {
+        BiRnn {
+            fwd: fwd,
+            bwd: bwd,
+        }
+    }
+
+    /// Get the forward builder.
+    pub fn fwd(&self) -> &B {
+        &self.fwd
+    }
+
+    /// Get the backward builder.
+    pub fn bwd(&self) -> &B {
+        &self.bwd
+    }
+
+    /// Load the parameters of both builders into the computation graph `cg`.
+    ///
+    /// See `RnnBuilder::new_graph`.
+    pub fn new_graph(&self, cg: &ComputationGraph, update: bool) {
+        self.fwd.new_graph(cg, update);
+        self.bwd.new_graph(cg, update);
+    }
+
+    /// Encode the sequence `xs`.
+    ///
+    /// The elements of `xs` can be batched, in which case the sequences of the
+    /// batch may have different lengths: `masks` then gives, for each
+    /// position, a batched `{1}` expression which is 1 for the batch elements
+    /// having a token at this position and 0 for the padded ones. The state of
+    /// each builder is carried unchanged over the padded positions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `xs` is empty, or if `masks` and `xs` have different lengths.
+    pub fn transduce(&self, xs: &[Expression], masks: Option<&[Expression]>) -> BiRnnOutput {
+        if xs.is_empty() {
+            panic!("Unable to encode an empty sequence.");
+        }
+        if let Some(ms) = masks {
+            if ms.len() != xs.len() {
+                panic!("The sequence has {} positions, but {} masks were provided.", xs.len(), ms.len());
+            }
+        }
+
+        let fwd_outputs = run(&self.fwd, xs.iter().enumerate(), masks);
+        let mut bwd_outputs = run(&self.bwd, xs.iter().enumerate().rev(), masks);
+        bwd_outputs.reverse();
+
+        BiRnnOutput {
+            states: fwd_outputs.into_iter().zip(bwd_outputs)
+                .map(|(f, b)| concatenate(&[f, b]))
+                .collect(),
+            final_fwd: self.fwd.final_s(),
+            final_bwd: self.bwd.final_s(),
+        }
+    }
+}
+
+/// Run `builder` over the inputs `xs` (with their positions) and return the
+/// output at each step.
+///
+/// With masks, each step adds two states to the builder: the one reached by
+/// the input and the masked one given to `set_s`, from which the next input
+/// continues.
+fn run<'a, B, I>(builder: &B, xs: I, masks: Option<&[Expression]>) -> Vec<Expression>
+    where B: RnnBuilder, I: Iterator<Item=(usize, &'a Expression)>
+{
+    builder.start_new_sequence(None);
+    let mut outputs = Vec::new();
+    // The state before the current step, None for the initial (zero) state.
+    let mut prev: Option<Vec<Expression>> = None;
+    for (t, x) in xs {
+        let h = builder.add_input(x);
+        match masks {
+            None => outputs.push(h),
+            Some(ms) => {
+                let m = &ms[t];
+                let s: Vec<Expression> = match prev {
+                    None => builder.final_s().iter().map(|s| cmult(m, s)).collect(),
+                    Some(ref p) => builder.final_s().iter().zip(p)
+                        .map(|(s, p)| select(m, s, p))
+                        .collect(),
+                };
+                builder.set_s(&s);
+                outputs.push(cmult(m, &h));
+                prev = Some(s);
+            }
+        }
+    }
+    outputs
+}
@@ -0,0 +1,48 @@
+extern crate dynet_sys as dn;
+
+use std::cell::Cell;
+
+use super::super::ParameterCollection;
+
+/// Gated recurrent unit, as described in
+/// [Cho et al., 2014](https://arxiv.org/abs/1406.1078).
+pub struct GRUBuilder {
+    inner: *mut dn::DN_GRUBuilder,
+    layers: u32,
+    sequence: Cell<usize>,
+    dropout: Cell<[f32; 3]>,
+}
+
+impl GRUBuilder {
+    /// Create a GRU with `layers` stacked layers, taking inputs of size
+    /// `input_dim` and with hidden states of size `hidden_dim`.
+    ///
+    /// The parameters are added to `pc`.
+    pub fn new(layers: u32, input_dim: u32, hidden_dim: u32, pc: &ParameterCollection) -> Self {
+        unsafe {
+            let inner = dn::DN_NewGRUBuilder(layers, input_dim, hidden_dim, pc.inner);
+            assert!(!inner.is_null());
+            GRUBuilder {
+                inner: inner,
+                layers: layers,
+                sequence: Cell::new(0),
+                dropout: Cell::new([0.0; 3]),
+            }
+        }
+    }
+
+    fn apply_dropout(&self) {
+        unsafe {
+            dn::DN_RNNBuilderSetDropout(self.as_rnn(), self.dropout.get()[0]);
+        }
+    }
+
+    fn clear_dropout(&self) {
+        unsafe {
+            dn::DN_RNNBuilderDisableDropout(self.as_rnn());
+        }
+    }
+}
+
+impl_drop!(GRUBuilder, DN_DeleteGRUBuilder);
+impl_rnn_builder!(GRUBuilder);
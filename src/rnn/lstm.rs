@@ -0,0 +1,238 @@
+extern crate dynet_sys as dn;
+
+use std::cell::Cell;
+
+use super::super::{ParameterCollection, Expression};
+use super::RnnBuilder;
+
+// The state of the LSTM builders (`final_s`) holds the memory cells of every
+// layer followed by their hidden states.
+macro_rules! impl_final_c {
+    ($name: ident) => {
+        impl $name {
+            /// Get the memory cell of each layer after the last input, from the
+            /// first layer to the last one.
+            pub fn final_c(&self) -> Vec<Expression> {
+                let mut s = self.final_s();
+                s.truncate(self.layers as usize);
+                s
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// VanillaLSTMBuilder
+////////////////////////////////////////////////////////////////////////////////
+
+/// Standard LSTM with peephole-free gates, as described in
+/// [Hochreiter and Schmidhuber, 1997](https://www.bioinf.jku.at/publications/older/2604.pdf)
+/// with the forget gate of [Gers et al., 2000](https://doi.org/10.1162/089976600300015015).
+///
+/// `final_s` holds the memory cells of every layer followed by their hidden
+/// states.
+///
+/// Dropout follows [Gal and Ghahramani, 2016](https://arxiv.org/abs/1512.05287)
+/// (variational dropout): the same masks are used for all the time steps of a
+/// sequence.
+pub struct VanillaLSTMBuilder {
+    inner: *mut dn::DN_VanillaLSTMBuilder,
+    layers: u32,
+    sequence: Cell<usize>,
+    dropout: Cell<[f32; 3]>,
+}
+
+impl VanillaLSTMBuilder {
+    /// Create an LSTM with `layers` stacked layers, taking inputs of size
+    /// `input_dim` and with hidden states of size `hidden_dim`.
+    ///
+    /// If `ln_lstm` is `true`, layer normalization is applied to the gates, as
+    /// described in [Ba et al., 2016](https://arxiv.org/abs/1607.06450).
+    ///
+    /// The parameters are added to `pc`.
+    pub fn new(layers: u32, input_dim: u32, hidden_dim: u32, pc: &ParameterCollection,
+               ln_lstm: bool) -> Self {
+        unsafe {
+            let inner = dn::DN_NewVanillaLSTMBuilder(layers, input_dim, hidden_dim, pc.inner, ln_lstm);
+            assert!(!inner.is_null());
+            VanillaLSTMBuilder {
+                inner: inner,
+                layers: layers,
+                sequence: Cell::new(0),
+                dropout: Cell::new([0.0; 3]),
+            }
+        }
+    }
+
+    /// Set the dropout rates `d` of the inputs and `d_h` of the recurrent
+    /// hidden states.
+    pub fn set_dropout_rates(&self, d: f32, d_h: f32) {
+        self.dropout.set([d, d_h, 0.0]);
+        self.sync_dropout();
+    }
+
+    /// Sample new dropout masks for a batch of size `batch_size`.
+    ///
+    /// Call this after `start_new_sequence` when the inputs are batched,
+    /// otherwise the same mask is applied to all the batch elements.
+    pub fn set_dropout_masks(&self, batch_size: u32) {
+        unsafe {
+            dn::DN_VanillaLSTMBuilderSetDropoutMasks(self.inner, batch_size);
+        }
+    }
+
+    fn apply_dropout(&self) {
+        let d = self.dropout.get();
+        unsafe {
+            dn::DN_VanillaLSTMBuilderSetDropoutRates(self.inner, d[0], d[1]);
+        }
+    }
+
+    fn clear_dropout(&self) {
+        unsafe {
+            dn::DN_VanillaLSTMBuilderSetDropoutRates(self.inner, 0.0, 0.0);
+        }
+    }
+}
+
+impl_drop!(VanillaLSTMBuilder, DN_DeleteVanillaLSTMBuilder);
+impl_rnn_builder!(VanillaLSTMBuilder);
+impl_final_c!(VanillaLSTMBuilder);
+
+////////////////////////////////////////////////////////////////////////////////
+// CoupledLSTMBuilder
+////////////////////////////////////////////////////////////////////////////////
+
+/// LSTM with peephole connections and coupled input and forget gates, as
+/// described in [Greff et al., 2017](https://arxiv.org/abs/1503.04069).
+///
+/// `final_s` holds the memory cells of every layer followed by their hidden
+/// states.
+pub struct CoupledLSTMBuilder {
+    inner: *mut dn::DN_CoupledLSTMBuilder,
+    layers: u32,
+    sequence: Cell<usize>,
+    dropout: Cell<[f32; 3]>,
+}
+
+impl CoupledLSTMBuilder {
+    /// Create a coupled LSTM with `layers` stacked layers, taking inputs of
+    /// size `input_dim` and with hidden states of size `hidden_dim`.
+    ///
+    /// The parameters are added to `pc`.
+    pub fn new(layers: u32, input_dim: u32, hidden_dim: u32, pc: &ParameterCollection) -> Self {
+        unsafe {
+            let inner = dn::DN_NewCoupledLSTMBuilder(layers, input_dim, hidden_dim, pc.inner);
+            assert!(!inner.is_null());
+            CoupledLSTMBuilder {
+                inner: inner,
+                layers: layers,
+                sequence: Cell::new(0),
+                dropout: Cell::new([0.0; 3]),
+            }
+        }
+    }
+
+    /// Set the dropout rates `d` of the inputs, `d_h` of the recurrent hidden
+    /// states and `d_c` of the memory cells.
+    pub fn set_dropout_rates(&self, d: f32, d_h: f32, d_c: f32) {
+        self.dropout.set([d, d_h, d_c]);
+        self.sync_dropout();
+    }
+
+    /// Sample new dropout masks for a batch of size `batch_size`.
+    ///
+    /// Call this after `start_new_sequence` when the inputs are batched,
+    /// otherwise the same mask is applied to all the batch elements.
+    pub fn set_dropout_masks(&self, batch_size: u32) {
+        unsafe {
+            dn::DN_CoupledLSTMBuilderSetDropoutMasks(self.inner, batch_size);
+        }
+    }
+
+    fn apply_dropout(&self) {
+        let d = self.dropout.get();
+        unsafe {
+            dn::DN_CoupledLSTMBuilderSetDropoutRates(self.inner, d[0], d[1], d[2]);
+        }
+    }
+
+    fn clear_dropout(&self) {
+        unsafe {
+            dn::DN_CoupledLSTMBuilderSetDropoutRates(self.inner, 0.0, 0.0, 0.0);
+        }
+    }
+}
+
+impl_drop!(CoupledLSTMBuilder, DN_DeleteCoupledLSTMBuilder);
+impl_rnn_builder!(CoupledLSTMBuilder);
+impl_final_c!(CoupledLSTMBuilder);
+
+////////////////////////////////////////////////////////////////////////////////
+// CompactVanillaLSTMBuilder
+////////////////////////////////////////////////////////////////////////////////
+
+/// Same model as `VanillaLSTMBuilder`, but computed with a single fused node
+/// per time step, which is faster and uses less memory.
+///
+/// `final_s` holds the memory cells of every layer followed by their hidden
+/// states.
+pub struct CompactVanillaLSTMBuilder {
+    inner: *mut dn::DN_CompactVanillaLSTMBuilder,
+    layers: u32,
+    sequence: Cell<usize>,
+    dropout: Cell<[f32; 3]>,
+}
+
+impl CompactVanillaLSTMBuilder {
+    /// Create a compact LSTM with `layers` stacked layers, taking inputs of
+    /// size `input_dim` and with hidden states of size `hidden_dim`.
+    ///
+    /// The parameters are added to `pc`.
+    pub fn new(layers: u32, input_dim: u32, hidden_dim: u32, pc: &ParameterCollection) -> Self {
+        unsafe {
+            let inner = dn::DN_NewCompactVanillaLSTMBuilder(layers, input_dim, hidden_dim, pc.inner);
+            assert!(!inner.is_null());
+            CompactVanillaLSTMBuilder {
+                inner: inner,
+                layers: layers,
+                sequence: Cell::new(0),
+                dropout: Cell::new([0.0; 3]),
+            }
+        }
+    }
+
+    /// Set the dropout rates `d` of the inputs and `d_r` of the recurrent
+    /// hidden states.
+    pub fn set_dropout_rates(&self, d: f32, d_r: f32) {
+        self.dropout.set([d, d_r, 0.0]);
+        self.sync_dropout();
+    }
+
+    /// Sample new dropout masks for a batch of size `batch_size`.
+    ///
+    /// Call this after `start_new_sequence` when the inputs are batched,
+    /// otherwise the same mask is applied to all the batch elements.
+    pub fn set_dropout_masks(&self, batch_size: u32) {
+        unsafe {
+            dn::DN_CompactVanillaLSTMBuilderSetDropoutMasks(self.inner, batch_size);
+        }
+    }
+
+    fn apply_dropout(&self) {
+        let d = self.dropout.get();
+        unsafe {
+            dn::DN_CompactVanillaLSTMBuilderSetDropoutRates(self.inner, d[0], d[1]);
+        }
+    }
+
+    fn clear_dropout(&self) {
+        unsafe {
+            dn::DN_CompactVanillaLSTMBuilderSetDropoutRates(self.inner, 0.0, 0.0);
+        }
+    }
+}
+
+impl_drop!(CompactVanillaLSTMBuilder, DN_DeleteCompactVanillaLSTMBuilder);
+impl_rnn_builder!(CompactVanillaLSTMBuilder);
+impl_final_c!(CompactVanillaLSTMBuilder);
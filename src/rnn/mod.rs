@@ -0,0 +1,327 @@
+//! Recurrent neural network builders.
+//!
+//! A builder holds the parameters of a recurrent network, which live in a
+//! `ParameterCollection`, and builds the recurrent computation in a
+//! `ComputationGraph` one input at a time.
+//!
+//! # Example
+//! ```
+//! use dynet::rnn::{RnnBuilder, SimpleRNNBuilder};
+//!
+//! let pc = dynet::ParameterCollection::new();
+//! let builder = SimpleRNNBuilder::new(1, 10, 20, &pc);
+//!
+//! let cg = dynet::ComputationGraph::new();
+//! builder.new_graph(&cg, true);
+//! builder.start_new_sequence(None);
+//! let x = cg.add_input(&[0.0; 10], &[10]);
+//! let h = builder.add_input(&x);
+//! ```
+
+extern crate dynet_sys as dn;
+
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::{ComputationGraph, Expression};
+
+/// Handle to a state of a recurrent builder.
+///
+/// Every input added to a builder creates a new state, which can be extended
+/// later with `RnnBuilder::add_input_from`. Several inputs can thus be added
+/// from the same state, which is needed for beam search or for stack-based
+/// parsers.
+///
+/// Handles are only valid for the builder and the sequence they were created
+/// in: the methods taking a handle panic when given one from another builder,
+/// or from before the last call to `new_graph` or `start_new_sequence`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RnnPointer {
+    index: i32,
+    sequence: usize,
+}
+
+static NEXT_SEQUENCE: AtomicUsize = AtomicUsize::new(1);
+
+// Get a number identifying a new sequence, unique across all the builders.
+fn next_sequence() -> usize {
+    NEXT_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Interface shared by the recurrent network builders.
+///
+/// Call `new_graph` once for each new computation graph, then
+/// `start_new_sequence` before each sequence and `add_input` for each of its
+/// elements.
+pub trait RnnBuilder {
+    /// Load the parameters of the builder into the computation graph `cg`.
+    ///
+    /// If `update` is `false`, the parameters are not updated during training.
+    ///
+    /// Dropout is only applied in training mode (see `set_training`), as read
+    /// when this method is called.
+    fn new_graph(&self, cg: &ComputationGraph, update: bool);
+
+    /// Start a new sequence, from the initial state `init` if provided and from
+    /// zero otherwise.
+    ///
+    /// `init` has the same layout as the result of `final_s`.
+    fn start_new_sequence(&self, init: Option<&[Expression]>);
+
+    /// Add the input `x` to the current sequence and return the output of the
+    /// last layer.
+    fn add_input(&self, x: &Expression) -> Expression;
+
+    /// Add the input `x` from the state `prev` instead of the current one,
+    /// and return the handle of the new state with the output of the last
+    /// layer.
+    ///
+    /// The new state becomes the current one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `prev` does not belong to the current sequence of this
+    /// builder.
+    fn add_input_from(&self, prev: RnnPointer, x: &Expression) -> (RnnPointer, Expression);
+
+    /// Get the handle of the current state, i.e. the state reached by the last
+    /// input, or the initial state right after `start_new_sequence`.
+    ///
+    /// `get_h` and `get_s` return an empty `Vec` for the initial state of a
+    /// sequence started without `init`.
+    fn state(&self) -> RnnPointer;
+
+    /// Get the output of the last layer for the last input.
+    fn back(&self) -> Expression;
+
+    /// Get the hidden state of each layer after the last input, from the first
+    /// layer to the last one.
+    ///
+    /// Empty before the first input of a sequence started without `init`.
+    fn final_h(&self) -> Vec<Expression>;
+
+    /// Get the full state of each layer after the last input.
+    ///
+    /// For most builders this is the same as `final_h`; for LSTMs it also
+    /// contains the memory cells. Empty before the first input of a sequence
+    /// started without `init`.
+    fn final_s(&self) -> Vec<Expression>;
+
+    /// Get the hidden state of each layer at the state `p`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` does not belong to the current sequence of this builder.
+    fn get_h(&self, p: RnnPointer) -> Vec<Expression>;
+
+    /// Get the full state of each layer at the state `p`, with the same layout
+    /// as `final_s`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` does not belong to the current sequence of this builder.
+    fn get_s(&self, p: RnnPointer) -> Vec<Expression>;
+
+    /// Add the state `s`, which has the same layout as the result of
+    /// `final_s`, as an alternative to the state reached by the last input,
+    /// and return its handle.
+    ///
+    /// The new state has the same predecessor as the state reached by the last
+    /// input and becomes the current one, so the next input continues from
+    /// `s`. Before the first input of a sequence, the new state follows the
+    /// initial state instead. The replaced state is not removed: its handle,
+    /// e.g. taken with `state` before this call, still refers to it. This is
+    /// used for example to keep the state unchanged over the padded positions
+    /// of a batch.
+    fn set_s(&self, s: &[Expression]) -> RnnPointer;
+
+    /// Set the dropout rate applied to the inputs and to all the recurrent
+    /// states.
+    ///
+    /// As in `new_graph`, dropout is only applied in training mode.
+    fn set_dropout(&self, d: f32);
+
+    /// Disable dropout.
+    fn disable_dropout(&self);
+}
+
+// Implements `RnnBuilder` through DyNet's common RNNBuilder interface. The
+// builder must have an `inner` pointer to the DyNet builder, a `layers` field,
+// a `sequence: Cell<usize>` field identifying its current sequence (0 before
+// the first one), a `dropout: Cell<[f32; 3]>` field holding its dropout
+// rates, an `apply_dropout` method setting these rates in DyNet and a
+// `clear_dropout` method setting all its rates to zero in DyNet. The base
+// class calls only reach the input dropout rate, hence the per-builder
+// methods.
+macro_rules! impl_rnn_builder {
+    ($name: ident) => {
+        impl $name {
+            fn as_rnn(&self) -> *mut dn::DN_RNNBuilder {
+                self.inner as *mut dn::DN_RNNBuilder
+            }
+
+            // Get the index of the state `p` in DyNet, after checking that it
+            // belongs to the current sequence.
+            fn check_pointer(&self, p: $crate::rnn::RnnPointer) -> i32 {
+                if p.sequence != self.sequence.get() {
+                    panic!("The state handle does not belong to the current sequence of this builder.");
+                }
+                p.index
+            }
+
+            // Set the cached dropout rates in DyNet in training mode, and
+            // zero rates otherwise.
+            fn sync_dropout(&self) {
+                if $crate::is_training() && self.dropout.get().iter().any(|&d| d > 0.0) {
+                    self.apply_dropout();
+                } else {
+                    self.clear_dropout();
+                }
+            }
+        }
+
+        impl $crate::rnn::RnnBuilder for $name {
+            fn new_graph(&self, cg: &$crate::ComputationGraph, update: bool) {
+                unsafe {
+                    dn::DN_RNNBuilderNewGraph(self.as_rnn(), cg.inner, update);
+                }
+                // The states of the previous graph can no longer be used.
+                self.sequence.set($crate::rnn::next_sequence());
+                self.sync_dropout();
+            }
+
+            fn start_new_sequence(&self, init: Option<&[$crate::Expression]>) {
+                let mut init_ptr: Vec<*mut dn::DN_Expression> = match init {
+                    Some(xs) => xs.iter().map(|x| x.inner).collect(),
+                    None => Vec::new(),
+                };
+                unsafe {
+                    dn::DN_RNNBuilderStartNewSequence(
+                        self.as_rnn(), init_ptr.as_mut_slice().as_mut_ptr(), init_ptr.len() as i32
+                    );
+                }
+                self.sequence.set($crate::rnn::next_sequence());
+            }
+
+            fn add_input(&self, x: &$crate::Expression) -> $crate::Expression {
+                unsafe {
+                    $crate::Expression{inner: dn::DN_RNNBuilderAddInput(self.as_rnn(), x.inner)}
+                }
+            }
+
+            fn add_input_from(&self, prev: $crate::rnn::RnnPointer, x: &$crate::Expression)
+                -> ($crate::rnn::RnnPointer, $crate::Expression)
+            {
+                unsafe {
+                    let h = $crate::Expression{
+                        inner: dn::DN_RNNBuilderAddInputFrom(self.as_rnn(), self.check_pointer(prev), x.inner)
+                    };
+                    (self.state(), h)
+                }
+            }
+
+            fn state(&self) -> $crate::rnn::RnnPointer {
+                unsafe {
+                    $crate::rnn::RnnPointer {
+                        index: dn::DN_RNNBuilderState(self.as_rnn()),
+                        sequence: self.sequence.get(),
+                    }
+                }
+            }
+
+            fn back(&self) -> $crate::Expression {
+                unsafe {
+                    $crate::Expression{inner: dn::DN_RNNBuilderBack(self.as_rnn())}
+                }
+            }
+
+            fn final_h(&self) -> Vec<$crate::Expression> {
+                unsafe {
+                    let mut hs = $crate::rnn::expr_buffer(self.layers as usize);
+                    let n = dn::DN_RNNBuilderFinalH(self.as_rnn(), hs.as_mut_ptr(), self.layers);
+                    $crate::rnn::from_buffer(hs, n)
+                }
+            }
+
+            fn final_s(&self) -> Vec<$crate::Expression> {
+                unsafe {
+                    let n = dn::DN_RNNBuilderNumH0Components(self.as_rnn());
+                    let mut ss = $crate::rnn::expr_buffer(n as usize);
+                    let n = dn::DN_RNNBuilderFinalS(self.as_rnn(), ss.as_mut_ptr(), n);
+                    $crate::rnn::from_buffer(ss, n)
+                }
+            }
+
+            fn get_h(&self, p: $crate::rnn::RnnPointer) -> Vec<$crate::Expression> {
+                unsafe {
+                    let mut hs = $crate::rnn::expr_buffer(self.layers as usize);
+                    let n = dn::DN_RNNBuilderGetH(self.as_rnn(), self.check_pointer(p), hs.as_mut_ptr(), self.layers);
+                    $crate::rnn::from_buffer(hs, n)
+                }
+            }
+
+            fn get_s(&self, p: $crate::rnn::RnnPointer) -> Vec<$crate::Expression> {
+                unsafe {
+                    let n = dn::DN_RNNBuilderNumH0Components(self.as_rnn());
+                    let mut ss = $crate::rnn::expr_buffer(n as usize);
+                    let n = dn::DN_RNNBuilderGetS(self.as_rnn(), self.check_pointer(p), ss.as_mut_ptr(), n);
+                    $crate::rnn::from_buffer(ss, n)
+                }
+            }
+
+            fn set_s(&self, s: &[$crate::Expression]) -> $crate::rnn::RnnPointer {
+                let mut s_ptr: Vec<*mut dn::DN_Expression> = s.iter().map(|x| x.inner).collect();
+                unsafe {
+                    // The initial state has no entry in DyNet's heads, and a
+                    // state replacing it follows it like the first input.
+                    let cur = dn::DN_RNNBuilderState(self.as_rnn());
+                    let prev = if cur < 0 { -1 } else { dn::DN_RNNBuilderGetHead(self.as_rnn(), cur) };
+                    dn::DN_RNNBuilderSetS(
+                        self.as_rnn(), prev, s_ptr.as_mut_slice().as_mut_ptr(), s_ptr.len() as i32
+                    );
+                }
+                self.state()
+            }
+
+            fn set_dropout(&self, d: f32) {
+                self.dropout.set([d; 3]);
+                self.sync_dropout();
+            }
+
+            fn disable_dropout(&self) {
+                self.dropout.set([0.0; 3]);
+                self.clear_dropout();
+            }
+        }
+    }
+}
+
+fn expr_buffer(n: usize) -> Vec<*mut dn::DN_Expression> {
+    vec![ptr::null_mut(); n]
+}
+
+// Wraps the first `n` expressions of `buf`, which DyNet reported as written.
+// The rest of the buffer holds null pointers, e.g. for the empty initial state
+// of a sequence started without `init`.
+fn from_buffer(mut buf: Vec<*mut dn::DN_Expression>, n: u32) -> Vec<Expression> {
+    buf.truncate(n as usize);
+    buf.into_iter().map(|inner| Expression{inner: inner}).collect()
+}
+
+mod simple;
+pub use self::simple::SimpleRNNBuilder;
+
+mod lstm;
+pub use self::lstm::{VanillaLSTMBuilder, CoupledLSTMBuilder, CompactVanillaLSTMBuilder};
+
+mod gru;
+pub use self::gru::GRUBuilder;
+
+mod tree;
+pub use self::tree::{TreeLstmBuilder, NaryTreeLSTMBuilder, UnidirectionalTreeLSTMBuilder, BidirectionalTreeLSTMBuilder};
+
+mod birnn;
+pub use self::birnn::{BiRnn, BiRnnOutput};
+
+mod stack;
+pub use self::stack::StackLstm;
@@ -0,0 +1,49 @@
+extern crate dynet_sys as dn;
+
+use std::cell::Cell;
+
+use super::super::ParameterCollection;
+
+/// Simple recurrent network (Elman network).
+///
+/// Each layer computes `h_t = tanh(W_x * x_t + W_h * h_{t-1} + b)`.
+pub struct SimpleRNNBuilder {
+    inner: *mut dn::DN_SimpleRNNBuilder,
+    layers: u32,
+    sequence: Cell<usize>,
+    dropout: Cell<[f32; 3]>,
+}
+
+impl SimpleRNNBuilder {
+    /// Create a simple recurrent network with `layers` stacked layers, taking
+    /// inputs of size `input_dim` and with hidden states of size `hidden_dim`.
+    ///
+    /// The parameters are added to `pc`.
+    pub fn new(layers: u32, input_dim: u32, hidden_dim: u32, pc: &ParameterCollection) -> Self {
+        unsafe {
+            let inner = dn::DN_NewSimpleRNNBuilder(layers, input_dim, hidden_dim, pc.inner);
+            assert!(!inner.is_null());
+            SimpleRNNBuilder {
+                inner: inner,
+                layers: layers,
+                sequence: Cell::new(0),
+                dropout: Cell::new([0.0; 3]),
+            }
+        }
+    }
+
+    fn apply_dropout(&self) {
+        unsafe {
+            dn::DN_RNNBuilderSetDropout(self.as_rnn(), self.dropout.get()[0]);
+        }
+    }
+
+    fn clear_dropout(&self) {
+        unsafe {
+            dn::DN_RNNBuilderDisableDropout(self.as_rnn());
+        }
+    }
+}
+
+impl_drop!(SimpleRNNBuilder, DN_DeleteSimpleRNNBuilder);
+impl_rnn_builder!(SimpleRNNBuilder);
@@ -0,0 +1,143 @@
+use super::super::{ParameterCollection, Parameter, ComputationGraph, Expression};
+use super::{RnnBuilder, RnnPointer};
+
+struct Entry {
+    pointer: RnnPointer,
+    input: Expression,
+    output: Expression,
+}
+
+/// Stack whose content is summarized by a recurrent network, as described in
+/// [Dyer et al., 2015](https://arxiv.org/abs/1505.08075).
+///
+/// Pushing an element adds it to the builder from the state of the top of the
+/// stack; popping it moves back to the previous state. The embedding of the
+/// stack is the output of the builder at its top.
+///
+/// The bottom of the stack holds a learned guard element, so that the empty
+/// stack also has an embedding.
+///
+/// # Example
+/// ```
+/// use dynet::rnn::{StackLstm, VanillaLSTMBuilder};
+///
+/// let pc = dynet::ParameterCollection::new();
+/// let mut stack = StackLstm::new(VanillaLSTMBuilder::new(1, 10, 20, &pc, false), 10, &pc);
+///
+/// let cg = dynet::ComputationGraph::new();
+/// stack.new_graph(&cg, true);
+/// let x = cg.add_input(&[0.0; 10], &[10]);
+/// stack.push(&x);
+/// stack.push(&x);
+/// stack.pop();
+/// let s = stack.embedding();
+/// assert_eq!(1, stack.len());
+/// ```
+pub struct StackLstm<B: RnnBuilder> {
+    builder: B,
+    p_guard: Parameter,
+    guard: Option<Expression>,
+    entries: Vec<Entry>,
+}
+
+impl<B: RnnBuilder> StackLstm<B> {
+    /// Create a stack over `builder`, which takes inputs of size `input_dim`,
+    /// and add the guard element to `pc`.
+    pub fn new(builder: B, input_dim: u32, pc: &ParameterCollection) -> Self {
+        StackLstm {
+            builder: builder,
+            p_guard: pc.add_default_param(&[input_dim]),
+            guard: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Get the underlying builder.
+    pub fn builder(&self) -> &B {
+        &self.builder
+    }
+
+    /// Load the parameters into the computation graph `cg` and start an empty
+    /// stack.
+    ///
+    /// See `RnnBuilder::new_graph`.
+    pub fn new_graph(&mut self, cg: &ComputationGraph, update: bool) {
+        self.builder.new_graph(cg, update);
+        self.guard = Some(if update { cg.load_param(&self.p_guard) } else { cg.load_const_param(&self.p_guard) });
+        self.start_new_stack();
+    }
+
+    /// Empty the stack, for example to process another sentence in the same
+    /// computation graph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_graph` has not been called.
+    pub fn start_new_stack(&mut self) {
+        let guard = self.guard.clone().expect("Call new_graph before using the stack");
+        self.entries.clear();
+        self.builder.start_new_sequence(None);
+        let (pointer, output) = self.builder.add_input_from(self.builder.state(), &guard);
+        self.entries.push(Entry{pointer: pointer, input: guard, output: output});
+    }
+
+    /// Push `x` on the stack.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_graph` has not been called.
+    pub fn push(&mut self, x: &Expression) {
+        let pointer = self.entries.last().expect("Call new_graph before using the stack").pointer;
+        let (pointer, output) = self.builder.add_input_from(pointer, x);
+        self.entries.push(Entry{pointer: pointer, input: x.clone(), output: output});
+    }
+
+    /// Remove the element at the top of the stack and return it, or `None` if
+    /// the stack is empty.
+    pub fn pop(&mut self) -> Option<Expression> {
+        if self.is_empty() {
+            return None;
+        }
+        self.entries.pop().map(|e| e.input)
+    }
+
+    /// Get the element at the top of the stack, or `None` if the stack is
+    /// empty.
+    pub fn top(&self) -> Option<&Expression> {
+        if self.is_empty() {
+            return None;
+        }
+        self.entries.last().map(|e| &e.input)
+    }
+
+    /// Get the embedding of the whole stack, i.e. the output of the last layer
+    /// of the builder at the top of the stack.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_graph` has not been called.
+    pub fn embedding(&self) -> Expression {
+        self.entries.last().expect("Call new_graph before using the stack").output.clone()
+    }
+
+    /// Get the full state of the builder at the top of the stack, with the
+    /// same layout as `RnnBuilder::final_s`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_graph` has not been called.
+    pub fn state(&self) -> Vec<Expression> {
+        let pointer = self.entries.last().expect("Call new_graph before using the stack").pointer;
+        self.builder.get_s(pointer)
+    }
+
+    /// Get the number of elements on the stack, not counting the guard.
+    pub fn len(&self) -> usize {
+        self.entries.len().saturating_sub(1)
+    }
+
+    /// Check whether the stack is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
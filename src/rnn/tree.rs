@@ -0,0 +1,225 @@
+extern crate dynet_sys as dn;
+
+use std::cell::RefCell;
+
+use super::super::{ParameterCollection, ComputationGraph, Expression};
+
+/// Interface shared by the Tree-LSTM builders.
+///
+/// Call `new_graph` once for each new computation graph, then
+/// `start_new_tree` before each tree and `add_input` for each of its nodes.
+/// A node must be added after all its children.
+///
+/// # Example
+/// ```
+/// use dynet::rnn::{TreeLstmBuilder, UnidirectionalTreeLSTMBuilder};
+///
+/// let pc = dynet::ParameterCollection::new();
+/// let builder = UnidirectionalTreeLSTMBuilder::new(1, 10, 20, &pc);
+///
+/// // The tree (2 (0) (1)).
+/// let cg = dynet::ComputationGraph::new();
+/// builder.new_graph(&cg, true);
+/// builder.start_new_tree(3);
+/// let x = cg.add_input(&[0.0; 10], &[10]);
+/// builder.add_input(0, &[], &x);
+/// builder.add_input(1, &[], &x);
+/// let root = builder.add_input(2, &[0, 1], &x);
+/// ```
+pub trait TreeLstmBuilder {
+    /// Load the parameters of the builder into the computation graph `cg`.
+    ///
+    /// If `update` is `false`, the parameters are not updated during training.
+    fn new_graph(&self, cg: &ComputationGraph, update: bool);
+
+    /// Start a new tree with `num_nodes` nodes, identified by the numbers
+    /// `0..num_nodes`.
+    fn start_new_tree(&self, num_nodes: u32);
+
+    /// Add the node `id` with input `x` and the children `children`, which
+    /// must already have been added, and return the representation of the
+    /// node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not a node of the current tree or has already been
+    /// added, if a child has not been added yet, or if the builder does not
+    /// support that many children.
+    fn add_input(&self, id: u32, children: &[u32], x: &Expression) -> Expression;
+}
+
+// Check that the node `id` with the children `children` can be added to the
+// tree whose nodes are flagged in `added` when already added, by a builder
+// supporting at most `max_children` children per node, and flag it.
+fn add_node(added: &mut [bool], max_children: Option<u32>, id: u32, children: &[u32]) {
+    if let Some(n) = max_children {
+        if children.len() > n as usize {
+            panic!("The node {} has {} children, but at most {} are supported.", id, children.len(), n);
+        }
+    }
+    match added.get(id as usize) {
+        None => panic!("The node {} does not exist, the tree has {} nodes.", id, added.len()),
+        Some(&true) => panic!("The node {} has already been added.", id),
+        Some(&false) => {}
+    }
+    for &c in children {
+        if !added.get(c as usize).cloned().unwrap_or(false) {
+            panic!("The child {} of the node {} has not been added yet.", c, id);
+        }
+    }
+    added[id as usize] = true;
+}
+
+// Implements `TreeLstmBuilder` through DyNet's common TreeLSTMBuilder
+// interface. The builder must have an `inner` pointer to the DyNet builder, an
+// `added: RefCell<Vec<bool>>` field flagging the nodes of the current tree
+// already added and a `max_children` method giving the maximum number of
+// children of a node, if there is one.
+macro_rules! impl_tree_lstm_builder {
+    ($name: ident) => {
+        impl $name {
+            fn as_tree(&self) -> *mut dn::DN_TreeLSTMBuilder {
+                self.inner as *mut dn::DN_TreeLSTMBuilder
+            }
+        }
+
+        impl TreeLstmBuilder for $name {
+            fn new_graph(&self, cg: &ComputationGraph, update: bool) {
+                unsafe {
+                    dn::DN_TreeLSTMBuilderNewGraph(self.as_tree(), cg.inner, update);
+                }
+            }
+
+            fn start_new_tree(&self, num_nodes: u32) {
+                *self.added.borrow_mut() = vec![false; num_nodes as usize];
+                unsafe {
+                    dn::DN_TreeLSTMBuilderStartNewSequence(self.as_tree());
+                    dn::DN_TreeLSTMBuilderSetNumElements(self.as_tree(), num_nodes);
+                }
+            }
+
+            fn add_input(&self, id: u32, children: &[u32], x: &Expression) -> Expression {
+                add_node(&mut self.added.borrow_mut(), self.max_children(), id, children);
+                unsafe {
+                    Expression{inner: dn::DN_TreeLSTMBuilderAddInput(
+                        self.as_tree(), id, children.as_ptr(), children.len(), x.inner
+                    )}
+                }
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// NaryTreeLSTMBuilder
+////////////////////////////////////////////////////////////////////////////////
+
+/// N-ary Tree-LSTM, as described in
+/// [Tai et al., 2015](https://arxiv.org/abs/1503.00075).
+///
+/// Each node has at most `n` ordered children, and each child position has
+/// its own parameters.
+pub struct NaryTreeLSTMBuilder {
+    inner: *mut dn::DN_NaryTreeLSTMBuilder,
+    n: u32,
+    added: RefCell<Vec<bool>>,
+}
+
+impl NaryTreeLSTMBuilder {
+    /// Create an N-ary Tree-LSTM for nodes with at most `n` children, with
+    /// `layers` stacked layers, taking inputs of size `input_dim` and with
+    /// hidden states of size `hidden_dim`.
+    ///
+    /// The parameters are added to `pc`.
+    pub fn new(n: u32, layers: u32, input_dim: u32, hidden_dim: u32, pc: &ParameterCollection) -> Self {
+        unsafe {
+            let inner = dn::DN_NewNaryTreeLSTMBuilder(n, layers, input_dim, hidden_dim, pc.inner);
+            assert!(!inner.is_null());
+            NaryTreeLSTMBuilder{inner: inner, n: n, added: RefCell::new(Vec::new())}
+        }
+    }
+
+    fn max_children(&self) -> Option<u32> {
+        Some(self.n)
+    }
+}
+
+impl_drop!(NaryTreeLSTMBuilder, DN_DeleteNaryTreeLSTMBuilder);
+impl_tree_lstm_builder!(NaryTreeLSTMBuilder);
+
+////////////////////////////////////////////////////////////////////////////////
+// UnidirectionalTreeLSTMBuilder
+////////////////////////////////////////////////////////////////////////////////
+
+/// Tree-LSTM which runs an LSTM over the representations of the children of
+/// each node, from the first child to the last one, followed by the input of
+/// the node.
+///
+/// Nodes can have any number of children.
+pub struct UnidirectionalTreeLSTMBuilder {
+    inner: *mut dn::DN_UnidirectionalTreeLSTMBuilder,
+    added: RefCell<Vec<bool>>,
+}
+
+impl UnidirectionalTreeLSTMBuilder {
+    /// Create a unidirectional Tree-LSTM with `layers` stacked layers, taking
+    /// inputs of size `input_dim` and with hidden states of size `hidden_dim`.
+    ///
+    /// The parameters are added to `pc`.
+    pub fn new(layers: u32, input_dim: u32, hidden_dim: u32, pc: &ParameterCollection) -> Self {
+        unsafe {
+            let inner = dn::DN_NewUnidirectionalTreeLSTMBuilder(layers, input_dim, hidden_dim, pc.inner);
+            assert!(!inner.is_null());
+            UnidirectionalTreeLSTMBuilder{inner: inner, added: RefCell::new(Vec::new())}
+        }
+    }
+
+    fn max_children(&self) -> Option<u32> {
+        None
+    }
+}
+
+impl_drop!(UnidirectionalTreeLSTMBuilder, DN_DeleteUnidirectionalTreeLSTMBuilder);
+impl_tree_lstm_builder!(UnidirectionalTreeLSTMBuilder);
+
+////////////////////////////////////////////////////////////////////////////////
+// BidirectionalTreeLSTMBuilder
+////////////////////////////////////////////////////////////////////////////////
+
+/// Tree-LSTM which runs an LSTM over the children of each node in both
+/// directions and concatenates the two results.
+///
+/// Nodes can have any number of children.
+pub struct BidirectionalTreeLSTMBuilder {
+    inner: *mut dn::DN_BidirectionalTreeLSTMBuilder,
+    added: RefCell<Vec<bool>>,
+}
+
+impl BidirectionalTreeLSTMBuilder {
+    /// Create a bidirectional Tree-LSTM with `layers` stacked layers, taking
+    /// inputs of size `input_dim` and with node representations of size
+    /// `hidden_dim` (half of it for each direction).
+    ///
+    /// The parameters are added to `pc`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hidden_dim` is odd.
+    pub fn new(layers: u32, input_dim: u32, hidden_dim: u32, pc: &ParameterCollection) -> Self {
+        if hidden_dim % 2 != 0 {
+            panic!("The hidden dimension of a bidirectional Tree-LSTM must be even, but {} was provided.", hidden_dim);
+        }
+        unsafe {
+            let inner = dn::DN_NewBidirectionalTreeLSTMBuilder(layers, input_dim, hidden_dim, pc.inner);
+            assert!(!inner.is_null());
+            BidirectionalTreeLSTMBuilder{inner: inner, added: RefCell::new(Vec::new())}
+        }
+    }
+
+    fn max_children(&self) -> Option<u32> {
+        None
+    }
+}
+
+impl_drop!(BidirectionalTreeLSTMBuilder, DN_DeleteBidirectionalTreeLSTMBuilder);
+impl_tree_lstm_builder!(BidirectionalTreeLSTMBuilder);
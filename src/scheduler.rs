@@ -0,0 +1,374 @@
+//! Learning rate schedules.
+//!
+//! A schedule gives the learning rate after a number of steps, as a function
+//! of the initial learning rate of the trainer. `Scheduled` attaches a
+//! schedule to a trainer and advances it after each update or each epoch.
+//!
+//! # Example
+//! ```
+//! use dynet::trainer::{Trainer, SimpleSGD};
+//! use dynet::scheduler::{Scheduled, StepUnit, LinearWarmup, CosineAnnealing};
+//!
+//! let pc = dynet::ParameterCollection::new();
+//! let schedule = LinearWarmup::new(100, CosineAnnealing::new(10000, 0.0));
+//! let trainer = Scheduled::new(SimpleSGD::new(&pc, 0.1), schedule, StepUnit::Update);
+//! // After each backward pass:
+//! // trainer.update();
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::f32::consts::PI;
+
+use super::ParameterCollection;
+use super::trainer::{Trainer, TrainerStatus};
+
+/// Interface shared by the learning rate schedules.
+pub trait LrSchedule {
+    /// Get the learning rate after `t` steps, where `base_lr` is the initial
+    /// learning rate.
+    fn lr(&self, base_lr: f32, t: u32) -> f32;
+
+    /// Report the value of a validation metric, lower being better, at the end
+    /// of an epoch.
+    ///
+    /// Only the schedules driven by a metric use it.
+    fn observe(&mut self, _metric: f32) {}
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Schedules
+////////////////////////////////////////////////////////////////////////////////
+
+/// Constant learning rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Constant;
+
+impl LrSchedule for Constant {
+    fn lr(&self, base_lr: f32, _t: u32) -> f32 {
+        base_lr
+    }
+}
+
+/// Learning rate multiplied by `gamma` every `step_size` steps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepDecay {
+    step_size: u32,
+    gamma: f32,
+}
+
+impl StepDecay {
+    /// Create a step decay multiplying the learning rate by `gamma` every
+    /// `step_size` steps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step_size` is 0.
+    pub fn new(step_size: u32, gamma: f32) -> Self {
+        if step_size == 0 {
+            panic!("The step size of a step decay must be positive.");
+        }
+        StepDecay{step_size: step_size, gamma: gamma}
+    }
+}
+
+impl LrSchedule for StepDecay {
+    fn lr(&self, base_lr: f32, t: u32) -> f32 {
+        base_lr * self.gamma.powi((t / self.step_size) as i32)
+    }
+}
+
+/// Learning rate multiplied by `gamma` at every step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExponentialDecay {
+    gamma: f32,
+}
+
+impl ExponentialDecay {
+    /// Create an exponential decay multiplying the learning rate by `gamma` at
+    /// every step.
+    pub fn new(gamma: f32) -> Self {
+        ExponentialDecay{gamma: gamma}
+    }
+}
+
+impl LrSchedule for ExponentialDecay {
+    fn lr(&self, base_lr: f32, t: u32) -> f32 {
+        base_lr * self.gamma.powi(t as i32)
+    }
+}
+
+/// Learning rate `base_lr / (1 + decay * t)`, as DyNet's `learning_rate_decay`
+/// applied at each epoch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InverseTimeDecay {
+    decay: f32,
+}
+
+impl InverseTimeDecay {
+    /// Create an inverse time decay with the decay rate `decay`.
+    pub fn new(decay: f32) -> Self {
+        InverseTimeDecay{decay: decay}
+    }
+}
+
+impl LrSchedule for InverseTimeDecay {
+    fn lr(&self, base_lr: f32, t: u32) -> f32 {
+        base_lr / (1.0 + self.decay * t as f32)
+    }
+}
+
+/// Learning rate increased linearly from `base_lr / steps` to `base_lr` during
+/// the first `steps` steps, then following another schedule, as described in
+/// [Goyal et al., 2017](https://arxiv.org/abs/1706.02677).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearWarmup<S: LrSchedule> {
+    steps: u32,
+    after: S,
+}
+
+impl<S: LrSchedule> LinearWarmup<S> {
+    /// Create a warmup of `steps` steps, followed by the schedule `after`,
+    /// whose steps are counted from the end of the warmup.
+    pub fn new(steps: u32, after: S) -> Self {
+        LinearWarmup{steps: steps, after: after}
+    }
+}
+
+impl<S: LrSchedule> LrSchedule for LinearWarmup<S> {
+    fn lr(&self, base_lr: f32, t: u32) -> f32 {
+        if t < self.steps {
+            base_lr * (t + 1) as f32 / self.steps as f32
+        } else {
+            self.after.lr(base_lr, t - self.steps)
+        }
+    }
+
+    fn observe(&mut self, metric: f32) {
+        self.after.observe(metric);
+    }
+}
+
+/// Learning rate decreased from `base_lr` to `min_lr` along a half cosine
+/// over `period` steps, then kept at `min_lr`, as described in
+/// [Loshchilov and Hutter, 2017](https://arxiv.org/abs/1608.03983).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CosineAnnealing {
+    period: u32,
+    min_lr: f32,
+}
+
+impl CosineAnnealing {
+    /// Create a cosine annealing to `min_lr` over `period` steps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is 0.
+    pub fn new(period: u32, min_lr: f32) -> Self {
+        if period == 0 {
+            panic!("The period of a cosine annealing must be positive.");
+        }
+        CosineAnnealing{period: period, min_lr: min_lr}
+    }
+}
+
+impl LrSchedule for CosineAnnealing {
+    fn lr(&self, base_lr: f32, t: u32) -> f32 {
+        if t >= self.period {
+            return self.min_lr;
+        }
+        let progress = t as f32 / self.period as f32;
+        self.min_lr + (base_lr - self.min_lr) * (1.0 + (PI * progress).cos()) / 2.0
+    }
+}
+
+/// Learning rate multiplied by `factor` when the validation metric has not
+/// improved for more than `patience` epochs, down to `min_lr`.
+///
+/// The metric is given to `observe`, usually through `Scheduled::end_epoch`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReduceOnPlateau {
+    factor: f32,
+    patience: u32,
+    min_lr: f32,
+    best: Option<f32>,
+    bad_epochs: u32,
+    scale: f32,
+}
+
+impl ReduceOnPlateau {
+    /// Create a schedule multiplying the learning rate by `factor` after more
+    /// than `patience` epochs without improvement, without going below
+    /// `min_lr`.
+    pub fn new(factor: f32, patience: u32, min_lr: f32) -> Self {
+        ReduceOnPlateau {
+            factor: factor,
+            patience: patience,
+            min_lr: min_lr,
+            best: None,
+            bad_epochs: 0,
+            scale: 1.0,
+        }
+    }
+}
+
+impl LrSchedule for ReduceOnPlateau {
+    fn lr(&self, base_lr: f32, _t: u32) -> f32 {
+        (base_lr * self.scale).max(self.min_lr)
+    }
+
+    fn observe(&mut self, metric: f32) {
+        match self.best {
+            Some(best) if metric >= best => {
+                self.bad_epochs += 1;
+                if self.bad_epochs > self.patience {
+                    self.scale *= self.factor;
+                    self.bad_epochs = 0;
+                }
+            }
+            _ => {
+                self.best = Some(metric);
+                self.bad_epochs = 0;
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Scheduled
+////////////////////////////////////////////////////////////////////////////////
+
+/// What a step of a schedule attached with `Scheduled` is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepUnit {
+    /// One step per call to `Scheduled::update`.
+    Update,
+    /// One step per call to `Scheduled::end_epoch`.
+    Epoch,
+}
+
+/// Trainer whose learning rate follows a schedule.
+///
+/// The initial learning rate of the schedule is the learning rate of the
+/// trainer when it is attached. `Scheduled` is itself a `Trainer`, so it can
+/// be used wherever the trainer it wraps can.
+pub struct Scheduled<T: Trainer> {
+    trainer: T,
+    schedule: RefCell<Box<dyn LrSchedule>>,
+    unit: StepUnit,
+    base_lr: Cell<f32>,
+    steps: Cell<u32>,
+}
+
+impl<T: Trainer> Scheduled<T> {
+    /// Attach the schedule `schedule`, advanced by steps of `unit`, to
+    /// `trainer`.
+    pub fn new<S: LrSchedule + 'static>(trainer: T, schedule: S, unit: StepUnit) -> Self {
+        let scheduled = Scheduled {
+            base_lr: Cell::new(trainer.learning_rate()),
+            trainer: trainer,
+            schedule: RefCell::new(Box::new(schedule)),
+            unit: unit,
+            steps: Cell::new(0),
+        };
+        scheduled.apply();
+        scheduled
+    }
+
+    /// Get the trainer, for example to read its status.
+    pub fn trainer(&self) -> &T {
+        &self.trainer
+    }
+
+    /// Get the number of steps of the schedule done so far.
+    pub fn steps(&self) -> u32 {
+        self.steps.get()
+    }
+
+    /// Detach the schedule and return the trainer, which keeps its current
+    /// learning rate.
+    pub fn into_trainer(self) -> T {
+        self.trainer
+    }
+
+    /// Signal the end of an epoch, with the value of the validation metric if
+    /// there is one, and advance the schedule if its steps are epochs.
+    pub fn end_epoch(&self, metric: Option<f32>) {
+        if let Some(m) = metric {
+            self.schedule.borrow_mut().observe(m);
+        }
+        if self.unit == StepUnit::Epoch {
+            self.steps.set(self.steps.get() + 1);
+        }
+        self.apply();
+    }
+
+    fn apply(&self) {
+        let lr = self.schedule.borrow().lr(self.base_lr.get(), self.steps.get());
+        self.trainer.set_learning_rate(lr);
+    }
+}
+
+impl<T: Trainer> Trainer for Scheduled<T> {
+    /// Create the default trainer with a constant schedule.
+    fn default(pc: &ParameterCollection) -> Self {
+        Scheduled::new(T::default(pc), Constant, StepUnit::Update)
+    }
+
+    /// Update the parameters, then advance the schedule if its steps are
+    /// updates.
+    fn update(&self) {
+        self.trainer.update();
+        if self.unit == StepUnit::Update {
+            self.steps.set(self.steps.get() + 1);
+            self.apply();
+        }
+    }
+
+    fn clip_gradients(&self) -> f32 {
+        self.trainer.clip_gradients()
+    }
+
+    fn learning_rate(&self) -> f32 {
+        self.trainer.learning_rate()
+    }
+
+    /// Set the initial learning rate of the schedule, and the current one
+    /// accordingly.
+    fn set_learning_rate(&self, lr: f32) {
+        self.base_lr.set(lr);
+        self.apply();
+    }
+
+    fn clip_threshold(&self) -> f32 {
+        self.trainer.clip_threshold()
+    }
+
+    fn set_clip_threshold(&self, threshold: f32) {
+        self.trainer.set_clip_threshold(threshold);
+    }
+
+    fn clipping_enabled(&self) -> bool {
+        self.trainer.clipping_enabled()
+    }
+
+    fn set_clipping_enabled(&self, enabled: bool) {
+        self.trainer.set_clipping_enabled(enabled);
+    }
+
+    fn sparse_updates_enabled(&self) -> bool {
+        self.trainer.sparse_updates_enabled()
+    }
+
+    fn set_sparse_updates_enabled(&self, enabled: bool) {
+        self.trainer.set_sparse_updates_enabled(enabled);
+    }
+
+    /// Reset the state of the trainer. The schedule keeps its position.
+    fn restart(&self) {
+        self.trainer.restart();
+    }
+
+    fn status(&self) -> TrainerStatus {
+        self.trainer.status()
+    }
+}
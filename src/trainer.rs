@@ -1,5 +1,7 @@
 extern crate dynet_sys as dn;
 
+use std::fmt;
+
 use super::{ParameterCollection};
 
 /// Optimizers that can be used to turn parameters.
@@ -15,6 +17,156 @@ pub trait Trainer {
     /// If clipping is enabled and the gradient is too big, return the amount to
     /// scale the gradient by (otherwise 1).
     fn clip_gradients(&self) -> f32;
+
+    /// Get the current learning rate.
+    fn learning_rate(&self) -> f32;
+
+    /// Set the learning rate used by the next updates.
+    fn set_learning_rate(&self, lr: f32);
+
+    /// Get the threshold above which the norm of the gradient is clipped.
+    fn clip_threshold(&self) -> f32;
+
+    /// Set the threshold above which the norm of the gradient is clipped
+    /// (5 by default).
+    fn set_clip_threshold(&self, threshold: f32);
+
+    /// Check whether the gradient is clipped.
+    fn clipping_enabled(&self) -> bool;
+
+    /// Enable or disable gradient clipping (enabled by default).
+    fn set_clipping_enabled(&self, enabled: bool);
+
+    /// Check whether only the rows of the `LookupParameter`s used since the
+    /// last update are updated.
+    fn sparse_updates_enabled(&self) -> bool;
+
+    /// Enable or disable sparse updates of the `LookupParameter`s (enabled by
+    /// default).
+    fn set_sparse_updates_enabled(&self, enabled: bool);
+
+    /// Reset the state of the optimizer, such as moving averages, as if no
+    /// update had been done.
+    fn restart(&self);
+
+    /// Get the learning rate and the number of updates and of clipped gradients
+    /// since the last call to `status`.
+    fn status(&self) -> TrainerStatus;
+}
+
+/// Result of `Trainer::status`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrainerStatus {
+    /// Current learning rate.
+    pub learning_rate: f32,
+    /// Number of clipped gradients since the last status.
+    pub clips: u32,
+    /// Number of updates since the last status.
+    pub updates: u32,
+}
+
+impl fmt::Display for TrainerStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[lr={} clips={} updates={}]", self.learning_rate, self.clips, self.updates)
+    }
+}
+
+// Implements `Trainer` with the DyNet functions `$update` and `$clip`, and
+// `default` with the expression `$default`, where `$pc` is the parameter
+// collection. The other methods go through DyNet's common Trainer interface.
+macro_rules! impl_trainer {
+    ($name: ident, $update: ident, $clip: ident, default($pc: ident) => $default: expr) => {
+        impl $name {
+            fn as_trainer(&self) -> *mut dn::DN_Trainer {
+                self.inner as *mut dn::DN_Trainer
+            }
+        }
+
+        impl Trainer for $name {
+            fn default($pc: &ParameterCollection) -> Self {
+                $default
+            }
+
+            fn update(&self) {
+                unsafe {
+                    dn::$update(self.inner);
+                }
+            }
+
+            fn clip_gradients(&self) -> f32 {
+                unsafe {
+                    dn::$clip(self.inner)
+                }
+            }
+
+            fn learning_rate(&self) -> f32 {
+                unsafe {
+                    dn::DN_TrainerGetLearningRate(self.as_trainer())
+                }
+            }
+
+            fn set_learning_rate(&self, lr: f32) {
+                unsafe {
+                    dn::DN_TrainerSetLearningRate(self.as_trainer(), lr);
+                }
+            }
+
+            fn clip_threshold(&self) -> f32 {
+                unsafe {
+                    dn::DN_TrainerGetClipThreshold(self.as_trainer())
+                }
+            }
+
+            fn set_clip_threshold(&self, threshold: f32) {
+                unsafe {
+                    dn::DN_TrainerSetClipThreshold(self.as_trainer(), threshold);
+                }
+            }
+
+            fn clipping_enabled(&self) -> bool {
+                unsafe {
+                    dn::DN_TrainerIsClippingEnabled(self.as_trainer())
+                }
+            }
+
+            fn set_clipping_enabled(&self, enabled: bool) {
+                unsafe {
+                    dn::DN_TrainerSetClippingEnabled(self.as_trainer(), enabled);
+                }
+            }
+
+            fn sparse_updates_enabled(&self) -> bool {
+                unsafe {
+                    dn::DN_TrainerIsSparseUpdatesEnabled(self.as_trainer())
+                }
+            }
+
+            fn set_sparse_updates_enabled(&self, enabled: bool) {
+                unsafe {
+                    dn::DN_TrainerSetSparseUpdates(self.as_trainer(), enabled);
+                }
+            }
+
+            fn restart(&self) {
+                unsafe {
+                    dn::DN_TrainerRestart(self.as_trainer());
+                }
+            }
+
+            fn status(&self) -> TrainerStatus {
+                let mut clips = 0;
+                let mut updates = 0;
+                unsafe {
+                    dn::DN_TrainerStatus(self.as_trainer(), &mut clips, &mut updates);
+                }
+                TrainerStatus {
+                    learning_rate: self.learning_rate(),
+                    clips: clips,
+                    updates: updates,
+                }
+            }
+        }
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -38,27 +190,352 @@ impl SimpleSGD {
 }
 
 impl_drop!(SimpleSGD, DN_DeleteSimpleSGDTrainer);
+impl_trainer!(SimpleSGD, DN_SimpleSGDTrainerUpdate, DN_SimpleSGDTrainerClipGradients,
+              default(pc) => SimpleSGD::new(pc, 0.1));
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Stochastic gradient descent trainer with momentum.
+///
+/// The update of each step is the gradient step added to the update of the
+/// previous step scaled by the momentum, which speeds up the descent along
+/// directions of consistent gradient.
+pub struct MomentumSGD {
+    inner: *mut dn::DN_MomentumSGDTrainer
+}
+
+impl MomentumSGD {
+    /// Create a stochastic gradient descent trainer with momentum, with the
+    /// initial learning rate `lr` and the momentum `mom`.
+    pub fn new(pc: &ParameterCollection, lr: f32, mom: f32) -> Self {
+        unsafe {
+            MomentumSGD{inner: dn::DN_NewMomentumSGDTrainer(pc.inner, lr, mom)}
+        }
+    }
+}
+
+impl_drop!(MomentumSGD, DN_DeleteMomentumSGDTrainer);
+impl_trainer!(MomentumSGD, DN_MomentumSGDTrainerUpdate, DN_MomentumSGDTrainerClipGradients,
+              default(pc) => MomentumSGD::new(pc, 0.01, 0.9));
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Superparameters of `Adagrad`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdagradParams {
+    /// Initial learning rate.
+    pub learning_rate: f32,
+    /// Bias parameter added to the accumulated squared gradients.
+    pub epsilon: f32,
+}
+
+impl Default for AdagradParams {
+    fn default() -> Self {
+        AdagradParams {
+            learning_rate: 0.1,
+            epsilon: 1e-20,
+        }
+    }
+}
+
+/// Adagrad trainer, as described in
+/// [Duchi et al., 2011](http://www.jmlr.org/papers/volume12/duchi11a/duchi11a.pdf).
+///
+/// The learning rate of each weight is divided by the square root of the sum
+/// of its past squared gradients.
+pub struct Adagrad {
+    inner: *mut dn::DN_AdagradTrainer
+}
+
+impl Adagrad {
+    /// Create an Adagrad trainer with the superparameters `params`.
+    pub fn new(pc: &ParameterCollection, params: AdagradParams) -> Self {
+        unsafe {
+            Adagrad{inner: dn::DN_NewAdagradTrainer(pc.inner, params.learning_rate, params.epsilon)}
+        }
+    }
+}
+
+impl_drop!(Adagrad, DN_DeleteAdagradTrainer);
+impl_trainer!(Adagrad, DN_AdagradTrainerUpdate, DN_AdagradTrainerClipGradients,
+              default(pc) => Adagrad::new(pc, AdagradParams::default()));
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Superparameters of `Adadelta`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdadeltaParams {
+    /// Bias parameter added to the running averages.
+    pub epsilon: f32,
+    /// Decay rate of the running averages.
+    pub rho: f32,
+}
+
+impl Default for AdadeltaParams {
+    fn default() -> Self {
+        AdadeltaParams {
+            epsilon: 1e-6,
+            rho: 0.95,
+        }
+    }
+}
 
-impl Trainer for SimpleSGD {
-    fn default(pc: &ParameterCollection) -> Self {
+/// Adadelta trainer, as described in
+/// [Zeiler, 2012](https://arxiv.org/abs/1212.5701).
+///
+/// The step of each weight is the ratio of the running averages of its past
+/// updates and of its past squared gradients, so there is no learning rate.
+pub struct Adadelta {
+    inner: *mut dn::DN_AdadeltaTrainer
+}
+
+impl Adadelta {
+    /// Create an Adadelta trainer with the superparameters `params`.
+    pub fn new(pc: &ParameterCollection, params: AdadeltaParams) -> Self {
+        unsafe {
+            Adadelta{inner: dn::DN_NewAdadeltaTrainer(pc.inner, params.epsilon, params.rho)}
+        }
+    }
+}
+
+impl_drop!(Adadelta, DN_DeleteAdadeltaTrainer);
+impl_trainer!(Adadelta, DN_AdadeltaTrainerUpdate, DN_AdadeltaTrainerClipGradients,
+              default(pc) => Adadelta::new(pc, AdadeltaParams::default()));
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Superparameters of `RMSProp`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RMSPropParams {
+    /// Initial learning rate.
+    pub learning_rate: f32,
+    /// Bias parameter added to the running average of the squared gradients.
+    pub epsilon: f32,
+    /// Decay rate of the running average of the squared gradients.
+    pub rho: f32,
+}
+
+impl Default for RMSPropParams {
+    fn default() -> Self {
+        RMSPropParams {
+            learning_rate: 0.1,
+            epsilon: 1e-20,
+            rho: 0.9,
+        }
+    }
+}
+
+/// RMSProp trainer, as described in
+/// [Hinton, 2012](http://www.cs.toronto.edu/~tijmen/csc321/slides/lecture_slides_lec6.pdf).
+///
+/// The learning rate of each weight is divided by the square root of a
+/// running average of its squared gradients.
+pub struct RMSProp {
+    inner: *mut dn::DN_RMSPropTrainer
+}
+
+impl RMSProp {
+    /// Create an RMSProp trainer with the superparameters `params`.
+    pub fn new(pc: &ParameterCollection, params: RMSPropParams) -> Self {
         unsafe {
-            SimpleSGD{inner: dn::DN_NewSimpleSGDTrainer(pc.inner, 0.1)}
+            RMSProp{inner: dn::DN_NewRMSPropTrainer(pc.inner, params.learning_rate, params.epsilon, params.rho)}
+        }
+    }
+}
+
+impl_drop!(RMSProp, DN_DeleteRMSPropTrainer);
+impl_trainer!(RMSProp, DN_RMSPropTrainerUpdate, DN_RMSPropTrainerClipGradients,
+              default(pc) => RMSProp::new(pc, RMSPropParams::default()));
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Superparameters of `Adam` and `AmsGrad`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdamParams {
+    /// Initial learning rate.
+    pub alpha: f32,
+    /// Decay rate of the running average of the gradients.
+    pub beta1: f32,
+    /// Decay rate of the running average of the squared gradients.
+    pub beta2: f32,
+    /// Bias parameter added to the square root of the running average of the
+    /// squared gradients.
+    pub epsilon: f32,
+    /// Whether only the rows of the `LookupParameter`s used since the last
+    /// update are updated. Otherwise the running averages move every row.
+    pub sparse_updates: bool,
+}
+
+impl Default for AdamParams {
+    fn default() -> Self {
+        AdamParams {
+            alpha: 0.001,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+            sparse_updates: true,
+        }
+    }
+}
+
+/// Adam trainer, as described in
+/// [Kingma and Ba, 2014](https://arxiv.org/abs/1412.6980).
+///
+/// The step of each weight is the running average of its gradients divided by
+/// the square root of the running average of its squared gradients, both
+/// corrected for their initialization bias.
+pub struct Adam {
+    inner: *mut dn::DN_AdamTrainer
+}
+
+impl Adam {
+    /// Create an Adam trainer with the superparameters `params`.
+    pub fn new(pc: &ParameterCollection, params: AdamParams) -> Self {
+        let trainer = unsafe {
+            Adam{inner: dn::DN_NewAdamTrainer(pc.inner, params.alpha, params.beta1, params.beta2, params.epsilon)}
+        };
+        trainer.set_sparse_updates_enabled(params.sparse_updates);
+        trainer
+    }
+}
+
+impl_drop!(Adam, DN_DeleteAdamTrainer);
+impl_trainer!(Adam, DN_AdamTrainerUpdate, DN_AdamTrainerClipGradients,
+              default(pc) => Adam::new(pc, AdamParams::default()));
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// AMSGrad trainer, as described in
+/// [Reddi et al., 2018](https://openreview.net/forum?id=ryQu7f-RZ).
+///
+/// Variant of `Adam` which divides by the maximum of the past running averages
+/// of the squared gradients, so that the step of a weight never grows because
+/// of a decrease of its gradients.
+pub struct AmsGrad {
+    inner: *mut dn::DN_AmsgradTrainer
+}
+
+impl AmsGrad {
+    /// Create an AMSGrad trainer with the superparameters `params`.
+    pub fn new(pc: &ParameterCollection, params: AdamParams) -> Self {
+        let trainer = unsafe {
+            AmsGrad{inner: dn::DN_NewAmsgradTrainer(pc.inner, params.alpha, params.beta1, params.beta2, params.epsilon)}
+        };
+        trainer.set_sparse_updates_enabled(params.sparse_updates);
+        trainer
+    }
+}
+
+impl_drop!(AmsGrad, DN_DeleteAmsgradTrainer);
+impl_trainer!(AmsGrad, DN_AmsgradTrainerUpdate, DN_AmsgradTrainerClipGradients,
+              default(pc) => AmsGrad::new(pc, AdamParams::default()));
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Superparameters of `CyclicalSGD`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CyclicalSGDParams {
+    /// Lower bound of the learning rate, which is also the initial one.
+    pub learning_rate_min: f32,
+    /// Upper bound of the learning rate.
+    pub learning_rate_max: f32,
+    /// Number of updates to go from one bound to the other.
+    pub step_size: f32,
+    /// Factor by which the amplitude of the cycles is multiplied after each
+    /// update.
+    pub gamma: f32,
+}
+
+impl Default for CyclicalSGDParams {
+    fn default() -> Self {
+        CyclicalSGDParams {
+            learning_rate_min: 0.01,
+            learning_rate_max: 0.1,
+            step_size: 2000.0,
+            gamma: 1.0,
         }
     }
+}
+
+/// Stochastic gradient descent trainer with a cyclical learning rate, as
+/// described in [Smith, 2017](https://arxiv.org/abs/1506.01186).
+///
+/// The learning rate goes linearly back and forth between its bounds, taking
+/// `step_size` updates in each direction, and the amplitude of the cycles
+/// decays by `gamma` at each update.
+pub struct CyclicalSGD {
+    inner: *mut dn::DN_CyclicalSGDTrainer
+}
 
-    fn update(&self) {
+impl CyclicalSGD {
+    /// Create a cyclical stochastic gradient descent trainer with the
+    /// superparameters `params`.
+    pub fn new(pc: &ParameterCollection, params: CyclicalSGDParams) -> Self {
         unsafe {
-            dn::DN_SimpleSGDTrainerUpdate(self.inner);
+            CyclicalSGD{inner: dn::DN_NewCyclicalSGDTrainer(
+                pc.inner, params.learning_rate_min, params.learning_rate_max, params.step_size, params.gamma
+            )}
         }
     }
 
-    fn clip_gradients(&self) -> f32 {
+    /// Move the learning rate one update further along the cycle.
+    ///
+    /// `update` already does this after updating the parameters, so call it
+    /// only to advance the schedule without an update. This overrides any
+    /// learning rate given to `set_learning_rate`.
+    pub fn update_lr(&self) {
         unsafe {
-            dn::DN_SimpleSGDTrainerClipGradients(self.inner)
+            dn::DN_CyclicalSGDTrainerUpdateLr(self.inner);
         }
-    } 
+    }
 }
 
+impl_drop!(CyclicalSGD, DN_DeleteCyclicalSGDTrainer);
+impl_trainer!(CyclicalSGD, DN_CyclicalSGDTrainerUpdate, DN_CyclicalSGDTrainerClipGradients,
+              default(pc) => CyclicalSGD::new(pc, CyclicalSGDParams::default()));
+
 ////////////////////////////////////////////////////////////////////////////////
 
+/// Superparameters of `EG`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EGParams {
+    /// Initial learning rate.
+    pub learning_rate: f32,
+    /// Momentum of the exponentiated updates.
+    pub momentum: f32,
+}
 
+impl Default for EGParams {
+    fn default() -> Self {
+        EGParams {
+            learning_rate: 0.1,
+            momentum: 0.9,
+        }
+    }
+}
+
+/// Exponentiated gradient trainer, as described in
+/// [Kivinen and Warmuth, 1997](https://doi.org/10.1006/inco.1996.2612).
+///
+/// Each parameter is multiplied by the exponential of its negative scaled
+/// gradient and normalized to sum to one, so it stays a probability
+/// distribution. The parameters must be initialized with positive values.
+pub struct EG {
+    inner: *mut dn::DN_EGTrainer
+}
+
+impl EG {
+    /// Create an exponentiated gradient trainer with the superparameters
+    /// `params`.
+    pub fn new(pc: &ParameterCollection, params: EGParams) -> Self {
+        unsafe {
+            EG{inner: dn::DN_NewEGTrainer(pc.inner, params.learning_rate, params.momentum)}
+        }
+    }
+}
+
+impl_drop!(EG, DN_DeleteEGTrainer);
+impl_trainer!(EG, DN_EGTrainerUpdate, DN_EGTrainerClipGradients,
+              default(pc) => EG::new(pc, EGParams::default()));
+
+////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,257 @@
+//! Transformer encoder, as described in
+//! [Vaswani et al., 2017](https://arxiv.org/abs/1706.03762).
+//!
+//! A sequence is given as a slice of `{d_model}` vectors, one per position,
+//! which can be batched. Add a positional encoding to the inputs, then stack
+//! `TransformerEncoderLayer`s.
+//!
+//! # Example
+//! ```
+//! use dynet::transformer::*;
+//!
+//! let pc = dynet::ParameterCollection::new();
+//! let pos = SinusoidalPositionalEncoding::new(16);
+//! let layers: Vec<_> = (0..2).map(|_| TransformerEncoderLayer::new(&pc, 16, 4, 64, 0.1)).collect();
+//!
+//! let cg = dynet::ComputationGraph::new();
+//! for layer in &layers {
+//!     layer.new_graph(&cg);
+//! }
+//! let xs: Vec<_> = (0..5).map(|_| cg.add_input(&[0.0; 16], &[16])).collect();
+//! let mut hs = pos.encode(&cg, &xs);
+//! for layer in &layers {
+//!     hs = layer.transduce(&hs, None);
+//! }
+//! ```
+
+use std::cell::RefCell;
+
+use super::{ParameterCollection, Parameter, LookupParameter, ComputationGraph, Expression, ParamInit};
+use super::ops::{affine_transform, colwise_add, concatenate, concatenate_cols, dropout,
+                 layer_norm, pick, rectify, softmax, transpose};
+use super::attention::mask_penalty;
+
+////////////////////////////////////////////////////////////////////////////////
+// Positional encodings
+////////////////////////////////////////////////////////////////////////////////
+
+/// Interface shared by the positional encodings.
+pub trait PositionalEncoding {
+    /// Add the encoding of each position to the inputs `xs`.
+    fn encode(&self, cg: &ComputationGraph, xs: &[Expression]) -> Vec<Expression>;
+}
+
+/// Fixed sinusoidal positional encoding.
+///
+/// The encoding of the position `pos` has the value
+/// `sin(pos / 10000^(i / dim))` at the even indices `i` and
+/// `cos(pos / 10000^((i - 1) / dim))` at the odd ones.
+pub struct SinusoidalPositionalEncoding {
+    dim: u32,
+}
+
+impl SinusoidalPositionalEncoding {
+    /// Create a sinusoidal positional encoding for inputs of size `dim`.
+    pub fn new(dim: u32) -> Self {
+        SinusoidalPositionalEncoding{dim: dim}
+    }
+
+    /// Get the values of the encoding of the position `pos`.
+    pub fn values(&self, pos: usize) -> Vec<f32> {
+        (0..self.dim).map(|i| {
+            let angle = pos as f32 / 10000f32.powf((i - i % 2) as f32 / self.dim as f32);
+            if i % 2 == 0 { angle.sin() } else { angle.cos() }
+        }).collect()
+    }
+}
+
+impl PositionalEncoding for SinusoidalPositionalEncoding {
+    fn encode(&self, cg: &ComputationGraph, xs: &[Expression]) -> Vec<Expression> {
+        xs.iter().enumerate()
+            .map(|(pos, x)| x + &cg.add_input(&self.values(pos), &[self.dim as i64]))
+            .collect()
+    }
+}
+
+/// Learned positional encoding: each position has its own embedding, which is
+/// trained with the rest of the model.
+pub struct LearnedPositionalEncoding {
+    p_pos: LookupParameter,
+    max_len: u32,
+}
+
+impl LearnedPositionalEncoding {
+    /// Create a learned positional encoding for inputs of size `dim` and
+    /// sequences of at most `max_len` positions, and add its parameters to
+    /// `pc`.
+    pub fn new(pc: &ParameterCollection, max_len: u32, dim: u32) -> Self {
+        LearnedPositionalEncoding {
+            p_pos: pc.add_lookup_param(&[max_len, dim], ParamInit::Normal(0.0, 0.02), "pos"),
+            max_len: max_len,
+        }
+    }
+}
+
+impl PositionalEncoding for LearnedPositionalEncoding {
+    /// # Panics
+    ///
+    /// Panics if `xs` is longer than the maximum length.
+    fn encode(&self, cg: &ComputationGraph, xs: &[Expression]) -> Vec<Expression> {
+        if xs.len() > self.max_len as usize {
+            panic!("The sequence has {} positions, but at most {} are supported.", xs.len(), self.max_len);
+        }
+        xs.iter().enumerate()
+            .map(|(pos, x)| x + &cg.lookup(&self.p_pos, pos as u32))
+            .collect()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TransformerEncoderLayer
+////////////////////////////////////////////////////////////////////////////////
+
+struct Head {
+    w_q: Parameter,
+    w_k: Parameter,
+    w_v: Parameter,
+}
+
+struct LoadedHead {
+    w_q: Expression,
+    w_k: Expression,
+    w_v: Expression,
+}
+
+struct Loaded {
+    heads: Vec<LoadedHead>,
+    w_o: Expression,
+    g_attn: Expression,
+    b_attn: Expression,
+    w_ff1: Expression,
+    b_ff1: Expression,
+    w_ff2: Expression,
+    b_ff2: Expression,
+    g_ff: Expression,
+    b_ff: Expression,
+}
+
+/// One layer of Transformer encoder: multi-head scaled dot-product
+/// self-attention followed by a position-wise feed-forward network, each with
+/// dropout, a residual connection and layer normalization.
+pub struct TransformerEncoderLayer {
+    heads: Vec<Head>,
+    w_o: Parameter,
+    g_attn: Parameter,
+    b_attn: Parameter,
+    w_ff1: Parameter,
+    b_ff1: Parameter,
+    w_ff2: Parameter,
+    b_ff2: Parameter,
+    g_ff: Parameter,
+    b_ff: Parameter,
+    d_k: u32,
+    dropout: f32,
+    loaded: RefCell<Option<Loaded>>,
+}
+
+impl TransformerEncoderLayer {
+    /// Create an encoder layer for inputs of size `d_model` with `num_heads`
+    /// attention heads, a feed-forward hidden layer of size `d_ff` and the
+    /// dropout rate `dropout`, and add its parameters to `pc`.
+    ///
+    /// Dropout is only applied in training mode, see `set_training`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `d_model` is not a multiple of `num_heads`.
+    pub fn new(pc: &ParameterCollection, d_model: u32, num_heads: u32, d_ff: u32, dropout: f32) -> Self {
+        if num_heads == 0 || d_model % num_heads != 0 {
+            panic!("The input size {} must be a multiple of the number of heads {}.", d_model, num_heads);
+        }
+        let d_k = d_model / num_heads;
+        TransformerEncoderLayer {
+            heads: (0..num_heads).map(|_| Head {
+                w_q: pc.add_default_param(&[d_k, d_model]),
+                w_k: pc.add_default_param(&[d_k, d_model]),
+                w_v: pc.add_default_param(&[d_k, d_model]),
+            }).collect(),
+            w_o: pc.add_default_param(&[d_model, d_model]),
+            g_attn: pc.add_param(&[d_model], ParamInit::Const(1.0), "ln-gain"),
+            b_attn: pc.add_param(&[d_model], ParamInit::Const(0.0), "ln-bias"),
+            w_ff1: pc.add_default_param(&[d_ff, d_model]),
+            b_ff1: pc.add_param(&[d_ff], ParamInit::Const(0.0), "ff-bias"),
+            w_ff2: pc.add_default_param(&[d_model, d_ff]),
+            b_ff2: pc.add_param(&[d_model], ParamInit::Const(0.0), "ff-bias"),
+            g_ff: pc.add_param(&[d_model], ParamInit::Const(1.0), "ln-gain"),
+            b_ff: pc.add_param(&[d_model], ParamInit::Const(0.0), "ln-bias"),
+            d_k: d_k,
+            dropout: dropout,
+            loaded: RefCell::new(None),
+        }
+    }
+
+    /// Load the parameters of the layer into the computation graph `cg`.
+    ///
+    /// Call this once for each new computation graph, before `transduce`.
+    pub fn new_graph(&self, cg: &ComputationGraph) {
+        *self.loaded.borrow_mut() = Some(Loaded {
+            heads: self.heads.iter().map(|h| LoadedHead {
+                w_q: cg.load_param(&h.w_q),
+                w_k: cg.load_param(&h.w_k),
+                w_v: cg.load_param(&h.w_v),
+            }).collect(),
+            w_o: cg.load_param(&self.w_o),
+            g_attn: cg.load_param(&self.g_attn),
+            b_attn: cg.load_param(&self.b_attn),
+            w_ff1: cg.load_param(&self.w_ff1),
+            b_ff1: cg.load_param(&self.b_ff1),
+            w_ff2: cg.load_param(&self.w_ff2),
+            b_ff2: cg.load_param(&self.b_ff2),
+            g_ff: cg.load_param(&self.g_ff),
+            b_ff: cg.load_param(&self.b_ff),
+        });
+    }
+
+    /// Encode the sequence `xs` and return the new representation of each
+    /// position.
+    ///
+    /// `mask` is an optional `{T}` vector (where `T` is the length of `xs`)
+    /// which is 1 for the positions to attend to and 0 for the padded ones.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `xs` is empty or if `new_graph` has not been called.
+    pub fn transduce(&self, xs: &[Expression], mask: Option<&Expression>) -> Vec<Expression> {
+        if xs.is_empty() {
+            panic!("Unable to encode an empty sequence.");
+        }
+        let loaded = self.loaded.borrow();
+        let p = loaded.as_ref().expect("Call new_graph before using the encoder layer");
+
+        // Multi-head self-attention over the {d_model, T} matrix of the inputs.
+        let x = concatenate_cols(xs);
+        let penalty = mask.map(mask_penalty);
+        let scale = 1.0 / (self.d_k as f32).sqrt();
+        let heads: Vec<Expression> = p.heads.iter().map(|h| {
+            let q = &h.w_q * &x;
+            let k = &h.w_k * &x;
+            let v = &h.w_v * &x;
+            // Column j holds the scores of the query j over all the keys.
+            let scores = &(&transpose(&k) * &q) * scale;
+            let scores = match penalty {
+                Some(ref m) => colwise_add(&scores, m),
+                None => scores,
+            };
+            &v * &softmax(&scores)
+        }).collect();
+        let attn = &p.w_o * &concatenate(&heads);
+
+        (0..xs.len() as u32).map(|t| {
+            let a = dropout(&pick(&attn, t, 1), self.dropout);
+            let y = layer_norm(&(&xs[t as usize] + &a), &p.g_attn, &p.b_attn);
+            let hidden = rectify(&affine_transform(&[p.b_ff1.clone(), p.w_ff1.clone(), y.clone()]));
+            let f = dropout(&affine_transform(&[p.b_ff2.clone(), p.w_ff2.clone(), hidden]), self.dropout);
+            layer_norm(&(&y + &f), &p.g_ff, &p.b_ff)
+        }).collect()
+    }
+}
@@ -0,0 +1,53 @@
+extern crate dynet as dy;
+
+use dy::attention::*;
+
+#[test]
+fn masked_positions_get_no_weight() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let attention = DotAttention::new();
+    attention.new_graph(&cg);
+
+    // Two positions with states (1, 2) and (3, 4), the second one is padded.
+    let states = cg.add_input(&[1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let query = cg.add_input(&[1.0, 1.0], &[2]);
+    let mask = cg.add_input(&[1.0, 0.0], &[2]);
+    let (context, weights) = attention.attend(&states, &query, Some(&mask));
+
+    assert_eq!(vec![1.0, 0.0], weights.value());
+    assert_eq!(vec![1.0, 2.0], context.value());
+}
+
+/// Attend with `attention` to two positions of size 2, the second one being
+/// padded, and check that all the weight goes to the first one.
+fn check_masked<A: Attention>(attention: &A, query_dim: usize) {
+    let cg = dy::ComputationGraph::new();
+    attention.new_graph(&cg);
+
+    let states = cg.add_input(&[1.0, 2.0, 3.0, 4.0], &[2, 2]);
+    let query = cg.add_input(&vec![1.0; query_dim], &[query_dim as i64]);
+    let mask = cg.add_input(&[1.0, 0.0], &[2]);
+    let (context, weights) = attention.attend(&states, &query, Some(&mask));
+
+    assert_eq!(vec![1.0, 0.0], weights.value());
+    assert_eq!(vec![1.0, 2.0], context.value());
+
+    // Without the mask, the weights still sum to one.
+    let (_, weights) = attention.attend(&states, &query, None);
+    assert!((weights.value().iter().sum::<f32>() - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn bilinear_attention_with_mask() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    check_masked(&BilinearAttention::new(&pc, 2, 3), 3);
+}
+
+#[test]
+fn mlp_attention_with_mask() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    check_masked(&MlpAttention::new(&pc, 2, 3, 5), 3);
+}
@@ -0,0 +1,57 @@
+extern crate dynet as dy;
+
+use dy::Dim;
+use dy::custom::{custom, CustomOp, TensorView, TensorViewMut};
+use dy::trainer::{Trainer, SimpleSGD};
+
+struct Cube;
+
+impl CustomOp for Cube {
+    fn dim(&self, xs: &[Dim]) -> Dim {
+        let d: Vec<u32> = (0..xs[0].ordre() as usize).map(|i| xs[0][i]).collect();
+        Dim::new(&d, xs[0].batch_size())
+    }
+
+    fn forward(&self, xs: &[TensorView], fx: &mut TensorViewMut) {
+        for (y, x) in fx.values_mut().iter_mut().zip(xs[0].values()) {
+            *y = x * x * x;
+        }
+    }
+
+    fn backward(&self, xs: &[TensorView], _fx: &TensorView, dedf: &TensorView,
+                _i: usize, dedxi: &mut TensorViewMut) {
+        let x = xs[0].values();
+        for (j, g) in dedxi.values_mut().iter_mut().enumerate() {
+            *g += 3.0 * x[j] * x[j] * dedf.values()[j];
+        }
+    }
+}
+
+#[test]
+fn custom_op_forward() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let x = cg.add_input(&[2.0], &[1]);
+    let y = custom(&[x], Cube);
+    assert_eq!(8.0, cg.forward(&y));
+}
+
+#[test]
+fn custom_op_backward() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let p = pc.add_param(&[1], dy::ParamInit::Const(1.0), "p");
+    let trainer = SimpleSGD::new(&pc, 0.1);
+
+    {
+        let cg = dy::ComputationGraph::new();
+        let y = custom(&[cg.load_param(&p)], Cube);
+        cg.forward(&y);
+        cg.backward(&y);
+        trainer.update();
+    }
+
+    // d(p^3)/dp = 3, so p = 1 - 0.1 * 3
+    let cg = dy::ComputationGraph::new();
+    assert!((cg.load_param(&p).value()[0] - 0.7).abs() < 1e-6);
+}
@@ -0,0 +1,176 @@
+extern crate dynet as dy;
+
+use dy::ops::*;
+use dy::trainer::{Trainer, SimpleSGD};
+
+#[test]
+fn constant_inputs() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let d = dy::Dim::new(&[4], 1);
+    let ones = cg.ones(&d);
+    let zeros = cg.zeros(&d);
+    assert_eq!(4.0, cg.forward(&dot_product(&ones, &ones)));
+    assert_eq!(0.0, cg.forward(&dot_product(&zeros, &ones)));
+    assert_eq!(2.5, cg.forward(&cg.constant(&dy::Dim::new(&[1], 1), 2.5)));
+    assert_eq!(-1.0, cg.forward(&cg.scalar_input(-1.0)));
+}
+
+#[test]
+fn random_inputs_respect_seed() {
+    dy::initialize();
+    let d = dy::Dim::new(&[1], 1);
+
+    dy::reset_rand_seed(42);
+    let first = {
+        let cg = dy::ComputationGraph::new();
+        cg.forward(&cg.random_normal(&d, 0.0, 1.0))
+    };
+
+    dy::reset_rand_seed(42);
+    let second = {
+        let cg = dy::ComputationGraph::new();
+        cg.forward(&cg.random_normal(&d, 0.0, 1.0))
+    };
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn random_uniform_range() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let x = cg.random_uniform(&dy::Dim::new(&[100], 1), -0.5, 2.0);
+    assert!(x.value().iter().all(|&v| v >= -0.5 && v <= 2.0));
+}
+
+#[test]
+fn random_bernoulli_values() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let x = cg.random_bernoulli(&dy::Dim::new(&[100], 1), 0.5, 3.0);
+    let vals = x.value();
+    assert!(vals.iter().all(|&v| v == 0.0 || v == 3.0));
+
+    let all = cg.random_bernoulli(&dy::Dim::new(&[10], 1), 1.0, 2.0);
+    assert_eq!(vec![2.0; 10], all.value());
+    let none = cg.random_bernoulli(&dy::Dim::new(&[10], 1), 0.0, 2.0);
+    assert_eq!(vec![0.0; 10], none.value());
+}
+
+#[test]
+fn random_gumbel_values() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let x = cg.random_gumbel(&dy::Dim::new(&[100], 1), 0.0, 1.0);
+    assert!(x.value().iter().all(|v| v.is_finite()));
+}
+
+#[test]
+#[should_panic(expected = "Only the standard Gumbel distribution")]
+fn random_gumbel_rejects_non_standard() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    cg.random_gumbel(&dy::Dim::new(&[1], 1), 1.0, 2.0);
+}
+
+#[test]
+fn input_handle_values_can_be_updated() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let x = cg.add_input_handle(&[1.0, 2.0], &[2]);
+    let ones = cg.ones(&dy::Dim::new(&[2], 1));
+    let y = dot_product(x.expr(), &ones);
+    assert_eq!(3.0, cg.forward(&y));
+    x.set_values(&[5.0, -1.0]);
+    assert_eq!(4.0, cg.forward(&y));
+}
+
+#[test]
+#[should_panic]
+fn input_handle_checks_length() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let x = cg.add_input_handle(&[1.0, 2.0], &[2]);
+    x.set_values(&[1.0]);
+}
+
+#[test]
+fn sparse_input() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let x = cg.add_sparse_input(&[5], &[1, 3], &[2.0, 4.0], 0.5);
+    let ones = cg.ones(&dy::Dim::new(&[5], 1));
+    assert_eq!(7.5, cg.forward(&dot_product(&x, &ones)));
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn sparse_input_id_out_of_range() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    cg.add_sparse_input(&[2, 3], &[1, 6], &[1.0, 1.0], 0.0);
+}
+
+/// Run one update of SGD with learning rate 0.1 on the loss `(e - 2)^2`,
+/// where `e` is the expression built by `build`.
+fn sgd_step<F: Fn(&dy::ComputationGraph) -> dy::Expression>(trainer: &SimpleSGD, build: F) {
+    let cg = dy::ComputationGraph::new();
+    let loss = squared_distance(&build(&cg), &cg.add_input(&[2.0], &[1]));
+    cg.forward(&loss);
+    cg.backward(&loss);
+    trainer.update();
+}
+
+#[test]
+fn const_lookup_and_const_param_are_not_updated() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let p = pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+    let lp = pc.add_lookup_param(&[2, 1], dy::ParamInit::Const(0.0), "lp");
+    let trainer = SimpleSGD::new(&pc, 0.1);
+
+    sgd_step(&trainer, |cg| cg.load_const_param(&p));
+    sgd_step(&trainer, |cg| cg.const_lookup(&lp, 0));
+    sgd_step(&trainer, |cg| cg.const_lookup_batch(&lp, &[1]));
+
+    let cg = dy::ComputationGraph::new();
+    assert_eq!(vec![0.0], cg.load_param(&p).value());
+    assert_eq!(vec![0.0, 0.0], cg.lookup_batch(&lp, &[0, 1]).value());
+}
+
+#[test]
+fn lookup_is_updated() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let lp = pc.add_lookup_param(&[2, 1], dy::ParamInit::Const(0.0), "lp");
+    let trainer = SimpleSGD::new(&pc, 0.1);
+
+    // Only the looked up row moves: 0 - 0.1 * (-4) = 0.4
+    sgd_step(&trainer, |cg| cg.lookup(&lp, 1));
+
+    let cg = dy::ComputationGraph::new();
+    let v = cg.lookup_batch(&lp, &[0, 1]).value();
+    assert_eq!(0.0, v[0]);
+    assert!((v[1] - 0.4).abs() < 1e-6);
+}
+
+#[test]
+fn gradient_control_ops() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let scaled = pc.add_param(&[1], dy::ParamInit::Const(0.0), "scaled");
+    let flipped = pc.add_param(&[1], dy::ParamInit::Const(0.0), "flipped");
+    let blocked = pc.add_param(&[1], dy::ParamInit::Const(0.0), "blocked");
+    let trainer = SimpleSGD::new(&pc, 0.1);
+
+    sgd_step(&trainer, |cg| scale_gradient(&cg.load_param(&scaled), 0.5));
+    sgd_step(&trainer, |cg| flip_gradient(&cg.load_param(&flipped)));
+    sgd_step(&trainer, |cg| nobackprop(&cg.load_param(&blocked)));
+
+    let cg = dy::ComputationGraph::new();
+    // Half of the plain SGD step 0.4, its opposite, and no step.
+    assert!((cg.load_param(&scaled).value()[0] - 0.2).abs() < 1e-6);
+    assert!((cg.load_param(&flipped).value()[0] + 0.4).abs() < 1e-6);
+    assert_eq!(vec![0.0], cg.load_param(&blocked).value());
+}
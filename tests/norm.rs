@@ -0,0 +1,50 @@
+extern crate dynet as dy;
+
+use dy::norm::BatchNorm;
+
+#[test]
+fn batch_norm_uses_running_stats_in_evaluation_mode() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let bn = BatchNorm::new(&pc, 1, 0.9, 0.0);
+    assert_eq!(vec![0.0], bn.running_mean());
+    assert_eq!(vec![1.0], bn.running_var());
+
+    dy::set_training(false);
+    let cg = dy::ComputationGraph::new();
+    let x = cg.add_input(&[2.0], &[1]);
+    let y = bn.apply(&cg, &x);
+    dy::set_training(true);
+    assert_eq!(2.0, cg.forward(&y));
+}
+
+#[test]
+fn batch_norm_uses_batch_stats_in_training_mode() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let bn = BatchNorm::new(&pc, 1, 0.9, 0.0);
+
+    let cg = dy::ComputationGraph::new();
+    // A batch of 4 elements with mean 3 and variance 3.5.
+    let x = cg.add_batched_input(&[1.0, 2.0, 3.0, 6.0], &[1, 4]);
+    let y = bn.apply(&cg, &x).value();
+
+    assert_eq!(4, y.len());
+    assert!(y.iter().sum::<f32>().abs() < 1e-5);
+    assert!((y[3] - 3.0 / 3.5f32.sqrt()).abs() < 1e-5);
+    // running = 0.9 * running + 0.1 * batch
+    assert!((bn.running_mean()[0] - 0.3).abs() < 1e-6);
+    assert!((bn.running_var()[0] - 1.25).abs() < 1e-6);
+}
+
+#[test]
+#[should_panic(expected = "at least 2 elements")]
+fn batch_norm_rejects_single_element_batch_in_training_mode() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let bn = BatchNorm::new(&pc, 1, 0.9, 0.0);
+
+    let cg = dy::ComputationGraph::new();
+    let x = cg.add_input(&[2.0], &[1]);
+    bn.apply(&cg, &x);
+}
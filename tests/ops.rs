@@ -0,0 +1,110 @@
+extern crate dynet as dy;
+
+use dy::ops::*;
+
+#[test]
+fn dot_product_of_vectors() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let x = cg.add_input(&[1.0, 2.0, 3.0], &[3]);
+    let y = cg.add_input(&[4.0, 5.0, 6.0], &[3]);
+    assert_eq!(32.0, cg.forward(&dot_product(&x, &y)));
+}
+
+#[test]
+fn affine_transform_matches_dense_layer() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let b = cg.add_input(&[1.0], &[1]);
+    let w = cg.add_input(&[2.0, 3.0], &[1, 2]);
+    let x = cg.add_input(&[4.0, 5.0], &[2]);
+    assert_eq!(24.0, cg.forward(&affine_transform(&[b, w, x])));
+}
+
+#[test]
+fn kmh_ngram_sums_columns() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let x = cg.add_input(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    assert_eq!(vec![4.0, 6.0, 8.0, 10.0], kmh_ngram(&x, 2).value());
+}
+
+#[test]
+fn conv2d_column_major_layout() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    // The 3x3 image
+    //   1 4 7
+    //   2 5 8
+    //   3 6 9
+    let x = cg.add_input(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0], &[3, 3, 1]);
+    // 2x2 filters selecting the top-left and the bottom-left pixels.
+    let top_left = cg.add_input(&[1.0, 0.0, 0.0, 0.0], &[2, 2, 1, 1]);
+    let bottom_left = cg.add_input(&[0.0, 1.0, 0.0, 0.0], &[2, 2, 1, 1]);
+
+    assert_eq!(vec![1.0, 2.0, 4.0, 5.0], conv2d(&x, &top_left, &[1, 1], Padding::Valid).value());
+    assert_eq!(vec![2.0, 3.0, 5.0, 6.0], conv2d(&x, &bottom_left, &[1, 1], Padding::Valid).value());
+    assert_eq!(9, conv2d(&x, &top_left, &[1, 1], Padding::Same).value().len());
+}
+
+#[test]
+fn maxpooling2d_windows() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let x = cg.add_input(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0], &[3, 3, 1]);
+
+    assert_eq!(vec![5.0, 6.0, 8.0, 9.0], maxpooling2d(&x, &[2, 2], &[1, 1], Padding::Valid).value());
+    assert_eq!(vec![5.0], maxpooling2d(&x, &[2, 2], &[2, 2], Padding::Valid).value());
+}
+
+#[test]
+fn dropout_is_identity_in_evaluation_mode() {
+    dy::initialize();
+    dy::set_training(false);
+    let cg = dy::ComputationGraph::new();
+    let x = cg.add_input(&[3.0], &[1]);
+    let y = dropout(&x, 0.9);
+    dy::set_training(true);
+    assert_eq!(3.0, cg.forward(&y));
+}
+
+#[test]
+fn topk_values_and_indices() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let x = cg.add_input(&[0.5, 3.0, -1.0, 2.0], &[4]);
+    let (vals, ids) = topk(&x, 2);
+    assert_eq!(vec![3.0, 2.0], vals);
+    assert_eq!(vec![1, 3], ids);
+}
+
+#[test]
+fn topk_with_nan() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let x = cg.add_input(&[0.5, ::std::f32::NAN, 2.0, 1.0], &[4]);
+    let (vals, ids) = topk(&x, 2);
+    assert!(vals[0].is_nan());
+    assert_eq!(2.0, vals[1]);
+    assert_eq!(vec![1, 2], ids);
+}
+
+#[test]
+#[should_panic]
+fn topk_rejects_batched_input() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let x = cg.add_batched_input(&[1.0, 3.0, 2.0, 0.0], &[2, 2]);
+    topk(&x, 1);
+}
+
+#[test]
+fn select_with_mask() {
+    dy::initialize();
+    let cg = dy::ComputationGraph::new();
+    let mask = cg.add_input(&[1.0, 0.0], &[2]);
+    let x = cg.add_input(&[1.0, 2.0], &[2]);
+    let y = cg.add_input(&[10.0, 20.0], &[2]);
+    let ones = cg.ones(&dy::Dim::new(&[2], 1));
+    assert_eq!(21.0, cg.forward(&dot_product(&select(&mask, &x, &y), &ones)));
+}
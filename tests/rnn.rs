@@ -0,0 +1,370 @@
+extern crate dynet as dy;
+
+use dy::rnn::*;
+
+#[test]
+fn simple_rnn_states() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let builder = SimpleRNNBuilder::new(2, 3, 4, &pc);
+
+    let cg = dy::ComputationGraph::new();
+    builder.new_graph(&cg, true);
+    builder.start_new_sequence(None);
+    let x = cg.add_input(&[1.0, 2.0, 3.0], &[3]);
+    builder.add_input(&x);
+    builder.add_input(&x);
+
+    assert_eq!(2, builder.final_h().len());
+    assert_eq!(2, builder.final_s().len());
+}
+
+#[test]
+fn empty_state_before_first_input() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let builder = VanillaLSTMBuilder::new(2, 3, 4, &pc, false);
+
+    let cg = dy::ComputationGraph::new();
+    builder.new_graph(&cg, true);
+    builder.start_new_sequence(None);
+    assert!(builder.final_h().is_empty());
+    assert!(builder.final_s().is_empty());
+    assert!(builder.final_c().is_empty());
+}
+
+#[test]
+fn lstm_recurrent_dropout_is_disabled_in_evaluation_mode() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let builder = VanillaLSTMBuilder::new(1, 3, 4, &pc, false);
+    builder.set_dropout_rates(0.0, 0.9);
+
+    dy::set_training(false);
+    let cg = dy::ComputationGraph::new();
+    builder.new_graph(&cg, true);
+    let x = cg.add_input(&[1.0, 2.0, 3.0], &[3]);
+    let mut runs = Vec::new();
+    for _ in 0..2 {
+        builder.start_new_sequence(None);
+        builder.add_input(&x);
+        runs.push(builder.add_input(&x).value());
+    }
+    dy::set_training(true);
+
+    assert_eq!(runs[0], runs[1]);
+}
+
+#[test]
+fn lstm_states() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let builder = VanillaLSTMBuilder::new(2, 3, 4, &pc, false);
+    builder.set_dropout_rates(0.5, 0.2);
+
+    let cg = dy::ComputationGraph::new();
+    builder.new_graph(&cg, true);
+    builder.start_new_sequence(None);
+    let x = cg.add_input(&[1.0, 2.0, 3.0], &[3]);
+    builder.add_input(&x);
+
+    assert_eq!(2, builder.final_h().len());
+    assert_eq!(2, builder.final_c().len());
+    assert_eq!(4, builder.final_s().len());
+}
+
+/// Feed two inputs to the two-layer LSTM `builder` and check that `final_s`
+/// holds the memory cells `c` of every layer followed by the hidden states.
+fn check_lstm_state_layout<B: RnnBuilder, F: Fn(&B) -> Vec<dy::Expression>>(builder: &B, final_c: F) {
+    let cg = dy::ComputationGraph::new();
+    builder.new_graph(&cg, true);
+    builder.start_new_sequence(None);
+    let x = cg.add_input(&[1.0, 2.0, 3.0], &[3]);
+    builder.add_input(&x);
+    builder.add_input(&x);
+
+    let h = builder.final_h();
+    let c = final_c(builder);
+    let s = builder.final_s();
+    assert_eq!(2, h.len());
+    assert_eq!(2, c.len());
+    assert_eq!(4, s.len());
+    for (ci, si) in c.iter().zip(&s[..2]) {
+        assert_eq!(ci.value(), si.value());
+    }
+    for (hi, si) in h.iter().zip(&s[2..]) {
+        assert_eq!(hi.value(), si.value());
+    }
+    assert_eq!(builder.back().value(), h[1].value());
+}
+
+#[test]
+fn layer_norm_lstm_states() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let builder = VanillaLSTMBuilder::new(2, 3, 4, &pc, true);
+    check_lstm_state_layout(&builder, |b| b.final_c());
+}
+
+#[test]
+fn coupled_lstm_states() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let builder = CoupledLSTMBuilder::new(2, 3, 4, &pc);
+    check_lstm_state_layout(&builder, |b| b.final_c());
+}
+
+#[test]
+fn compact_lstm_states() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let builder = CompactVanillaLSTMBuilder::new(2, 3, 4, &pc);
+    check_lstm_state_layout(&builder, |b| b.final_c());
+}
+
+#[test]
+fn gru_states_at_each_step() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let builder = GRUBuilder::new(2, 3, 4, &pc);
+
+    let cg = dy::ComputationGraph::new();
+    builder.new_graph(&cg, true);
+    builder.start_new_sequence(None);
+    let x = cg.add_input(&[1.0, 2.0, 3.0], &[3]);
+    builder.add_input(&x);
+    let first = builder.state();
+    builder.add_input(&x);
+
+    assert_eq!(2, builder.get_h(first).len());
+    assert_eq!(2, builder.get_h(builder.state()).len());
+}
+
+#[test]
+fn branching_from_previous_states() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let builder = SimpleRNNBuilder::new(1, 1, 1, &pc);
+
+    let cg = dy::ComputationGraph::new();
+    builder.new_graph(&cg, true);
+    builder.start_new_sequence(None);
+    let x = cg.add_input(&[1.0], &[1]);
+    let y = cg.add_input(&[-1.0], &[1]);
+    builder.add_input(&x);
+    let root = builder.state();
+
+    // Two branches from the same state.
+    let (p1, h1) = builder.add_input_from(root, &x);
+    let (p2, h2) = builder.add_input_from(root, &y);
+    assert!(p1 != p2);
+    assert_eq!(p2, builder.state());
+    assert_eq!(cg.forward(&h1), cg.forward(&builder.get_h(p1)[0]));
+    assert_eq!(cg.forward(&h2), cg.forward(&builder.get_h(p2)[0]));
+}
+
+#[test]
+fn set_s_adds_a_state() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let builder = SimpleRNNBuilder::new(1, 1, 1, &pc);
+
+    let cg = dy::ComputationGraph::new();
+    builder.new_graph(&cg, true);
+    builder.start_new_sequence(None);
+    let x = cg.add_input(&[1.0], &[1]);
+    let h = builder.add_input(&x);
+    let reached = builder.state();
+    let zero = cg.add_input(&[0.0], &[1]);
+    let replaced = builder.set_s(&[zero]);
+
+    assert!(replaced != reached);
+    assert_eq!(replaced, builder.state());
+    assert_eq!(vec![0.0], builder.get_h(replaced)[0].value());
+    // The replaced state is still reachable through its handle.
+    assert_eq!(h.value(), builder.get_h(reached)[0].value());
+}
+
+#[test]
+fn set_s_before_first_input() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let builder = SimpleRNNBuilder::new(1, 1, 1, &pc);
+
+    let cg = dy::ComputationGraph::new();
+    builder.new_graph(&cg, true);
+    builder.start_new_sequence(None);
+    let init = builder.state();
+    let zero = cg.add_input(&[0.0], &[1]);
+    let replaced = builder.set_s(&[zero]);
+
+    assert!(replaced != init);
+    assert_eq!(vec![0.0], builder.final_h()[0].value());
+    // The next input continues from the new state.
+    let x = cg.add_input(&[1.0], &[1]);
+    builder.add_input(&x);
+    assert_eq!(1, builder.final_h().len());
+}
+
+#[test]
+fn initial_state_is_empty_without_init() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let builder = VanillaLSTMBuilder::new(2, 1, 1, &pc, false);
+
+    let cg = dy::ComputationGraph::new();
+    builder.new_graph(&cg, true);
+    builder.start_new_sequence(None);
+    let init = builder.state();
+    assert!(builder.get_h(init).is_empty());
+    assert!(builder.get_s(init).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "does not belong to the current sequence")]
+fn state_handle_from_previous_sequence() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let builder = SimpleRNNBuilder::new(1, 1, 1, &pc);
+
+    let cg = dy::ComputationGraph::new();
+    builder.new_graph(&cg, true);
+    builder.start_new_sequence(None);
+    let x = cg.add_input(&[1.0], &[1]);
+    builder.add_input(&x);
+    let old = builder.state();
+    builder.start_new_sequence(None);
+    builder.get_h(old);
+}
+
+#[test]
+#[should_panic(expected = "does not belong to the current sequence")]
+fn state_handle_from_another_builder() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let first = SimpleRNNBuilder::new(1, 1, 1, &pc);
+    let second = SimpleRNNBuilder::new(1, 1, 1, &pc);
+
+    let cg = dy::ComputationGraph::new();
+    first.new_graph(&cg, true);
+    second.new_graph(&cg, true);
+    first.start_new_sequence(None);
+    second.start_new_sequence(None);
+    let x = cg.add_input(&[1.0], &[1]);
+    first.add_input(&x);
+    second.add_input_from(first.state(), &x);
+}
+
+#[test]
+fn birnn_with_masks() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let birnn = BiRnn::new(GRUBuilder::new(1, 2, 3, &pc), GRUBuilder::new(1, 2, 3, &pc));
+
+    let cg = dy::ComputationGraph::new();
+    birnn.new_graph(&cg, true);
+    // Two sequences of lengths 2 and 1.
+    let xs = vec![
+        cg.add_batched_input(&[1.0, 1.0, 1.0, 1.0], &[2, 2]),
+        cg.add_batched_input(&[1.0, 1.0, 0.0, 0.0], &[2, 2]),
+    ];
+    let masks = vec![
+        cg.add_batched_input(&[1.0, 1.0], &[1, 2]),
+        cg.add_batched_input(&[1.0, 0.0], &[1, 2]),
+    ];
+    let out = birnn.transduce(&xs, Some(&masks));
+
+    assert_eq!(2, out.states.len());
+    assert_eq!(1, out.final_fwd.len());
+    assert_eq!(1, out.final_bwd.len());
+    // The padded position of the second sequence is zero.
+    assert_eq!(&[0.0; 6], &out.states[1].value()[6..]);
+}
+
+#[test]
+fn stack_lstm_push_and_pop() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let mut stack = StackLstm::new(SimpleRNNBuilder::new(1, 1, 2, &pc), 1, &pc);
+
+    let cg = dy::ComputationGraph::new();
+    stack.new_graph(&cg, true);
+    assert!(stack.is_empty());
+    assert!(stack.pop().is_none());
+    let empty = stack.embedding().value();
+
+    let x = cg.add_input(&[1.0], &[1]);
+    let y = cg.add_input(&[-1.0], &[1]);
+    stack.push(&x);
+    let after_x = stack.embedding().value();
+    stack.push(&y);
+    assert_eq!(2, stack.len());
+    assert_eq!(vec![-1.0], stack.top().unwrap().value());
+
+    // Popping goes back to the embedding of the previous stack.
+    assert_eq!(vec![-1.0], stack.pop().unwrap().value());
+    assert_eq!(after_x, stack.embedding().value());
+    stack.pop();
+    assert_eq!(empty, stack.embedding().value());
+    assert!(stack.top().is_none());
+}
+
+/// Build the tree (2 (0) (1)) and return the representation of the root.
+fn small_tree<B: TreeLstmBuilder>(builder: &B, cg: &dy::ComputationGraph) -> Vec<f32> {
+    builder.new_graph(cg, true);
+    builder.start_new_tree(3);
+    let x = cg.add_input(&[1.0, 2.0, 3.0], &[3]);
+    builder.add_input(0, &[], &x);
+    builder.add_input(1, &[], &x);
+    builder.add_input(2, &[0, 1], &x).value()
+}
+
+#[test]
+fn tree_lstm_builders() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    {
+        let cg = dy::ComputationGraph::new();
+        assert_eq!(4, small_tree(&NaryTreeLSTMBuilder::new(2, 1, 3, 4, &pc), &cg).len());
+    }
+    {
+        let cg = dy::ComputationGraph::new();
+        assert_eq!(4, small_tree(&UnidirectionalTreeLSTMBuilder::new(1, 3, 4, &pc), &cg).len());
+    }
+    {
+        let cg = dy::ComputationGraph::new();
+        assert_eq!(4, small_tree(&BidirectionalTreeLSTMBuilder::new(1, 3, 4, &pc), &cg).len());
+    }
+}
+
+#[test]
+#[should_panic(expected = "has not been added yet")]
+fn tree_lstm_rejects_missing_child() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let builder = UnidirectionalTreeLSTMBuilder::new(1, 3, 4, &pc);
+
+    let cg = dy::ComputationGraph::new();
+    builder.new_graph(&cg, true);
+    builder.start_new_tree(3);
+    let x = cg.add_input(&[1.0, 2.0, 3.0], &[3]);
+    builder.add_input(0, &[], &x);
+    builder.add_input(2, &[0, 1], &x);
+}
+
+#[test]
+#[should_panic(expected = "at most 2 are supported")]
+fn nary_tree_lstm_rejects_extra_child() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let builder = NaryTreeLSTMBuilder::new(2, 1, 3, 4, &pc);
+
+    let cg = dy::ComputationGraph::new();
+    builder.new_graph(&cg, true);
+    builder.start_new_tree(4);
+    let x = cg.add_input(&[1.0, 2.0, 3.0], &[3]);
+    builder.add_input(0, &[], &x);
+    builder.add_input(1, &[], &x);
+    builder.add_input(2, &[], &x);
+    builder.add_input(3, &[0, 1, 2], &x);
+}
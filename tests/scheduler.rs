@@ -0,0 +1,100 @@
+extern crate dynet as dy;
+
+use dy::scheduler::*;
+use dy::trainer::*;
+
+fn assert_close(expected: f32, actual: f32) {
+    assert!((expected - actual).abs() < 1e-6, "expected {}, got {}", expected, actual);
+}
+
+#[test]
+fn decays() {
+    let step = StepDecay::new(2, 0.5);
+    assert_close(1.0, step.lr(1.0, 1));
+    assert_close(0.5, step.lr(1.0, 2));
+    assert_close(0.25, step.lr(1.0, 5));
+
+    assert_close(0.81, ExponentialDecay::new(0.9).lr(1.0, 2));
+    assert_close(0.25, InverseTimeDecay::new(0.5).lr(0.5, 2));
+}
+
+#[test]
+fn warmup_then_cosine() {
+    let schedule = LinearWarmup::new(4, CosineAnnealing::new(10, 0.1));
+    assert_close(0.25, schedule.lr(1.0, 0));
+    assert_close(1.0, schedule.lr(1.0, 3));
+    assert_close(1.0, schedule.lr(1.0, 4));
+    assert_close(0.55, schedule.lr(1.0, 9));
+    assert_close(0.1, schedule.lr(1.0, 14));
+    assert_close(0.1, schedule.lr(1.0, 100));
+}
+
+#[test]
+fn reduce_on_plateau() {
+    let mut schedule = ReduceOnPlateau::new(0.5, 1, 0.2);
+    for &metric in &[3.0, 2.0, 2.5] {
+        schedule.observe(metric);
+    }
+    assert_close(1.0, schedule.lr(1.0, 0));
+    // Second epoch without improvement.
+    schedule.observe(2.0);
+    assert_close(0.5, schedule.lr(1.0, 0));
+    schedule.observe(1.0);
+    schedule.observe(1.5);
+    schedule.observe(1.5);
+    assert_close(0.25, schedule.lr(1.0, 0));
+    schedule.observe(1.5);
+    schedule.observe(1.5);
+    assert_close(0.2, schedule.lr(1.0, 0));
+}
+
+#[test]
+fn scheduled_trainer() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+
+    let per_update = Scheduled::new(SimpleSGD::new(&pc, 0.2), StepDecay::new(1, 0.5), StepUnit::Update);
+    assert_close(0.2, per_update.trainer().learning_rate());
+    per_update.update();
+    per_update.update();
+    assert_eq!(2, per_update.steps());
+    assert_close(0.05, per_update.trainer().learning_rate());
+    per_update.end_epoch(None);
+    assert_close(0.05, per_update.trainer().learning_rate());
+
+    let per_epoch = Scheduled::new(SimpleSGD::new(&pc, 0.2), InverseTimeDecay::new(1.0), StepUnit::Epoch);
+    per_epoch.update();
+    assert_close(0.2, per_epoch.trainer().learning_rate());
+    per_epoch.end_epoch(None);
+    assert_close(0.1, per_epoch.trainer().learning_rate());
+
+    let plateau = Scheduled::new(SimpleSGD::new(&pc, 0.2), ReduceOnPlateau::new(0.1, 0, 0.0), StepUnit::Epoch);
+    plateau.end_epoch(Some(1.0));
+    plateau.end_epoch(Some(1.0));
+    assert_close(0.02, plateau.trainer().learning_rate());
+}
+
+fn generic_update<T: Trainer>(trainer: &T) {
+    trainer.update();
+}
+
+#[test]
+fn scheduled_trainer_is_a_trainer() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+
+    let trainer = Scheduled::new(SimpleSGD::new(&pc, 0.2), ExponentialDecay::new(0.5), StepUnit::Update);
+    generic_update(&trainer);
+    assert_close(0.1, trainer.learning_rate());
+    assert_eq!(1, trainer.status().updates);
+
+    // Changing the learning rate rescales the schedule.
+    trainer.set_learning_rate(0.4);
+    assert_close(0.2, trainer.learning_rate());
+
+    let default: Scheduled<SimpleSGD> = Trainer::default(&pc);
+    generic_update(&default);
+    assert_close(0.1, default.learning_rate());
+}
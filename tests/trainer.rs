@@ -0,0 +1,266 @@
+extern crate dynet as dy;
+
+use dy::ops::*;
+use dy::trainer::*;
+
+/// Run `steps` updates of `trainer` on the loss `(p - 2)^2`, where the
+/// parameter `p` starts at 0, and return the loss after the last update.
+fn quadratic_loss_after<T: Trainer>(p: &dy::Parameter, trainer: &T, steps: u32) -> f32 {
+    for _ in 0..steps {
+        let cg = dy::ComputationGraph::new();
+        let loss = squared_distance(&cg.load_param(p), &cg.add_input(&[2.0], &[1]));
+        cg.forward(&loss);
+        cg.backward(&loss);
+        trainer.update();
+    }
+    let cg = dy::ComputationGraph::new();
+    let loss = squared_distance(&cg.load_param(p), &cg.add_input(&[2.0], &[1]));
+    cg.forward(&loss)
+}
+
+#[test]
+fn simple_sgd_step() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let p = pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+    let trainer = SimpleSGD::new(&pc, 0.1);
+
+    // p = 0 - 0.1 * (-4) = 0.4
+    assert!((quadratic_loss_after(&p, &trainer, 1) - 2.56).abs() < 1e-5);
+}
+
+#[test]
+fn momentum_sgd_accumulates_velocity() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let p = pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+    let trainer = MomentumSGD::new(&pc, 0.1, 0.9);
+
+    // v = 0.4, p = 0.4, then v = 0.9 * 0.4 + 0.1 * 3.2 = 0.68, p = 1.08
+    assert!((quadratic_loss_after(&p, &trainer, 2) - 0.8464).abs() < 1e-5);
+}
+
+#[test]
+fn momentum_sgd_without_momentum_is_sgd() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let p = pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+    let trainer = MomentumSGD::new(&pc, 0.1, 0.0);
+
+    // p = 0.4, then p = 0.4 + 0.1 * 3.2 = 0.72
+    assert!((quadratic_loss_after(&p, &trainer, 2) - 1.6384).abs() < 1e-5);
+}
+
+#[test]
+fn adagrad_first_step_has_learning_rate_size() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let p = pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+    let trainer = Adagrad::new(&pc, AdagradParams{learning_rate: 0.1, ..Default::default()});
+
+    // p = 0 + 0.1 * 4 / sqrt(16) = 0.1
+    assert!((quadratic_loss_after(&p, &trainer, 1) - 3.61).abs() < 1e-5);
+}
+
+#[test]
+fn rmsprop_step() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let p = pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+    let trainer = RMSProp::new(&pc, RMSPropParams{learning_rate: 0.1, epsilon: 1e-20, rho: 0.9});
+
+    // v = 0.1 * 16, p = 0 + 0.1 * 4 / sqrt(1.6)
+    let p1 = 0.4 / 1.6f32.sqrt();
+    assert!((quadratic_loss_after(&p, &trainer, 1) - (2.0 - p1) * (2.0 - p1)).abs() < 1e-4);
+}
+
+#[test]
+fn default_trainers_decrease_the_loss() {
+    fn check<T: Trainer>() {
+        let pc = dy::ParameterCollection::new();
+        let p = pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+        let trainer = T::default(&pc);
+        assert!(quadratic_loss_after(&p, &trainer, 3) < 4.0);
+    }
+    dy::initialize();
+    check::<SimpleSGD>();
+    check::<MomentumSGD>();
+    check::<Adagrad>();
+    check::<Adadelta>();
+    check::<RMSProp>();
+    check::<Adam>();
+    check::<AmsGrad>();
+    check::<CyclicalSGD>();
+}
+
+#[test]
+fn adam_first_step_has_alpha_size() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let p = pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+    let trainer = Adam::new(&pc, AdamParams{alpha: 0.1, ..Default::default()});
+
+    // The bias-corrected averages are g and g^2, so p = 0.1
+    assert!((quadratic_loss_after(&p, &trainer, 1) - 3.61).abs() < 1e-4);
+}
+
+/// Update the rows 0 and 1 of a lookup parameter, then only the row 0, and
+/// return the value of the row 1.
+fn unused_row_after_updates<T: Trainer, F: Fn(&dy::ParameterCollection) -> T>(make: F) -> f32 {
+    let pc = dy::ParameterCollection::new();
+    let lp = pc.add_lookup_param(&[2, 1], dy::ParamInit::Const(0.0), "lp");
+    let trainer = make(&pc);
+    for ids in &[vec![0, 1], vec![0]] {
+        let cg = dy::ComputationGraph::new();
+        let xs: Vec<_> = ids.iter().map(|&i| cg.lookup(&lp, i)).collect();
+        let loss = squared_distance(&sum(&xs), &cg.add_input(&[2.0], &[1]));
+        cg.forward(&loss);
+        cg.backward(&loss);
+        trainer.update();
+    }
+    let cg = dy::ComputationGraph::new();
+    cg.lookup(&lp, 1).value()[0]
+}
+
+#[test]
+fn adam_sparse_updates() {
+    dy::initialize();
+    let sparse = unused_row_after_updates(|pc| Adam::new(pc, AdamParams{alpha: 0.1, ..Default::default()}));
+    let dense = unused_row_after_updates(|pc| Adam::new(pc, AdamParams{alpha: 0.1, sparse_updates: false, ..Default::default()}));
+    let amsgrad = unused_row_after_updates(|pc| AmsGrad::new(pc, AdamParams{alpha: 0.1, ..Default::default()}));
+
+    // With sparse updates, the row 1 keeps the value of the first update.
+    assert!((sparse - 0.1).abs() < 1e-4);
+    assert!((amsgrad - 0.1).abs() < 1e-4);
+    // Otherwise the running average of its gradients still moves it.
+    assert!(dense > sparse + 1e-3);
+}
+
+#[test]
+fn cyclical_sgd_moves_between_bounds() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let p = pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+    let trainer = CyclicalSGD::new(&pc, CyclicalSGDParams{
+        learning_rate_min: 0.1,
+        learning_rate_max: 0.5,
+        step_size: 1.0,
+        gamma: 1.0,
+    });
+
+    // p = 0 + 0.1 * 4 = 0.4 with the lower bound, then the learning rate
+    // reaches the upper bound and p = 0.4 + 0.5 * 3.2 = 2
+    assert!((quadratic_loss_after(&p, &trainer, 1) - 2.56).abs() < 1e-5);
+    assert!(quadratic_loss_after(&p, &trainer, 1) < 1e-5);
+}
+
+#[test]
+fn eg_keeps_a_distribution() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let p = pc.add_param(&[2], dy::ParamInit::Const(0.5), "p");
+    let trainer = EG::default(&pc);
+
+    let cg = dy::ComputationGraph::new();
+    let loss = squared_distance(&cg.load_param(&p), &cg.add_input(&[1.0, 0.0], &[2]));
+    cg.forward(&loss);
+    cg.backward(&loss);
+    trainer.update();
+
+    let cg = dy::ComputationGraph::new();
+    let v = cg.load_param(&p).value();
+    assert!(v[0] > 0.5);
+    assert!((v[0] + v[1] - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn learning_rate_can_be_changed() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let p = pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+    let trainer = SimpleSGD::new(&pc, 0.1);
+    assert!((trainer.learning_rate() - 0.1).abs() < 1e-7);
+
+    // p = 0 + 0.25 * 4 = 1
+    trainer.set_learning_rate(0.25);
+    assert!((quadratic_loss_after(&p, &trainer, 1) - 1.0).abs() < 1e-5);
+}
+
+/// Run one update of SGD with learning rate 0.1 on the loss `(p - 10)^2`,
+/// where `p` starts at 0, and return the new value of `p`.
+fn large_gradient_step(trainer: &SimpleSGD, p: &dy::Parameter) -> f32 {
+    let cg = dy::ComputationGraph::new();
+    let loss = squared_distance(&cg.load_param(p), &cg.add_input(&[10.0], &[1]));
+    cg.forward(&loss);
+    cg.backward(&loss);
+    trainer.update();
+    let cg = dy::ComputationGraph::new();
+    cg.load_param(p).value()[0]
+}
+
+#[test]
+fn gradient_clipping() {
+    dy::initialize();
+    {
+        // The gradient -20 is clipped to the default threshold 5.
+        let pc = dy::ParameterCollection::new();
+        let p = pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+        let trainer = SimpleSGD::new(&pc, 0.1);
+        assert!(trainer.clipping_enabled());
+        assert!((large_gradient_step(&trainer, &p) - 0.5).abs() < 1e-5);
+        assert_eq!(1, trainer.status().clips);
+    }
+    {
+        let pc = dy::ParameterCollection::new();
+        let p = pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+        let trainer = SimpleSGD::new(&pc, 0.1);
+        trainer.set_clip_threshold(10.0);
+        assert_eq!(10.0, trainer.clip_threshold());
+        assert!((large_gradient_step(&trainer, &p) - 1.0).abs() < 1e-5);
+    }
+    {
+        let pc = dy::ParameterCollection::new();
+        let p = pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+        let trainer = SimpleSGD::new(&pc, 0.1);
+        trainer.set_clipping_enabled(false);
+        assert!((large_gradient_step(&trainer, &p) - 2.0).abs() < 1e-5);
+        assert_eq!(0, trainer.status().clips);
+    }
+}
+
+#[test]
+fn sparse_updates_can_be_toggled() {
+    let pc = dy::ParameterCollection::new();
+    let trainer = Adam::new(&pc, AdamParams{sparse_updates: false, ..Default::default()});
+    assert!(!trainer.sparse_updates_enabled());
+    trainer.set_sparse_updates_enabled(true);
+    assert!(trainer.sparse_updates_enabled());
+}
+
+#[test]
+fn restart_forgets_the_momentum() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let p = pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+    let trainer = MomentumSGD::new(&pc, 0.1, 0.9);
+
+    // p = 0.4, then without the previous velocity p = 0.4 + 0.1 * 3.2 = 0.72
+    quadratic_loss_after(&p, &trainer, 1);
+    trainer.restart();
+    assert!((quadratic_loss_after(&p, &trainer, 1) - 1.6384).abs() < 1e-5);
+}
+
+#[test]
+fn status_counts_updates_since_last_call() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let p = pc.add_param(&[1], dy::ParamInit::Const(0.0), "p");
+    let trainer = SimpleSGD::new(&pc, 0.1);
+
+    quadratic_loss_after(&p, &trainer, 2);
+    let status = trainer.status();
+    assert_eq!(2, status.updates);
+    assert_eq!(0, status.clips);
+    assert_eq!("[lr=0.1 clips=0 updates=2]", status.to_string());
+    assert_eq!(0, trainer.status().updates);
+}
@@ -0,0 +1,45 @@
+extern crate dynet as dy;
+
+use dy::transformer::*;
+
+#[test]
+fn sinusoidal_encoding_values() {
+    let pos = SinusoidalPositionalEncoding::new(4);
+    assert_eq!(vec![0.0, 1.0, 0.0, 1.0], pos.values(0));
+    let v = pos.values(1);
+    assert!((v[0] - 1f32.sin()).abs() < 1e-6);
+    assert!((v[1] - 1f32.cos()).abs() < 1e-6);
+    assert!((v[2] - 0.01f32.sin()).abs() < 1e-6);
+    assert!((v[3] - 0.01f32.cos()).abs() < 1e-6);
+}
+
+#[test]
+fn encoder_layer_keeps_positions_and_size() {
+    dy::initialize();
+    let pc = dy::ParameterCollection::new();
+    let pos = LearnedPositionalEncoding::new(&pc, 10, 8);
+    let layer = TransformerEncoderLayer::new(&pc, 8, 2, 16, 0.1);
+
+    dy::set_training(false);
+    let cg = dy::ComputationGraph::new();
+    layer.new_graph(&cg);
+    let xs: Vec<_> = (0..3).map(|i| cg.add_input(&[i as f32; 8], &[8])).collect();
+    let mask = cg.add_input(&[1.0, 1.0, 0.0], &[3]);
+    let hs = layer.transduce(&pos.encode(&cg, &xs), Some(&mask));
+    dy::set_training(true);
+
+    assert_eq!(3, hs.len());
+    for h in &hs {
+        let v = h.value();
+        assert_eq!(8, v.len());
+        // The output of the layer normalization has zero mean.
+        assert!(v.iter().sum::<f32>().abs() < 1e-4);
+    }
+}
+
+#[test]
+#[should_panic]
+fn encoder_layer_rejects_indivisible_heads() {
+    let pc = dy::ParameterCollection::new();
+    TransformerEncoderLayer::new(&pc, 10, 3, 16, 0.0);
+}